@@ -0,0 +1,48 @@
+//! Announcements for screen readers. `iced` draws on a canvas that they can't
+//! read, so on the web results are also written to a visually hidden live
+//! region, which they read out whenever it changes. There's no such thing on
+//! desktop yet, as `iced` 0.12 has no accessibility support: announcing does
+//! nothing there, and only the window title carries the result.
+//!
+//! Keyboard users are limited too: Tab only moves between text inputs, as
+//! `iced` 0.12 buttons, pick lists and checkboxes can't take the focus.
+
+#[cfg(target_arch = "wasm32")]
+const LIVE_REGION: &str = "whatif-status";
+
+/// The live region, added to the page the first time
+#[cfg(target_arch = "wasm32")]
+fn live_region() -> Option<web_sys::Element> {
+    let document = web_sys::window()?.document()?;
+    if let Some(region) = document.get_element_by_id(LIVE_REGION) {
+        return Some(region);
+    }
+
+    let region = document.create_element("div").ok()?;
+    for (name, value) in [
+        ("id", LIVE_REGION),
+        ("role", "status"),
+        ("aria-live", "polite"),
+        // Hidden from sight only
+        (
+            "style",
+            "position: absolute; width: 1px; height: 1px; overflow: hidden; clip: rect(0 0 0 0);",
+        ),
+    ] {
+        region.set_attribute(name, value).ok()?;
+    }
+    document.body()?.append_child(&region).ok()?;
+    Some(region)
+}
+
+/// Has screen readers read `message` out, without interrupting the user
+#[cfg(target_arch = "wasm32")]
+pub fn announce(message: &str) {
+    if let Some(region) = live_region() {
+        region.set_text_content(Some(message));
+    }
+}
+
+/// Nothing to announce to on desktop, see the module documentation
+#[cfg(not(target_arch = "wasm32"))]
+pub fn announce(_message: &str) {}
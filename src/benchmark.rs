@@ -0,0 +1,360 @@
+//! What the same contributions, on the same dates, would have become in
+//! something other than bitcoin: cash, savings, or the S&P 500 and gold, whose
+//! month-end closes are bundled. The part of a scenario that isn't converted
+//! into bitcoin can be kept in one of them too.
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+    dollar::{DollarAmount, SignedDollarAmount},
+    fees::BASIS_POINTS,
+    price::{mul_div, Rounding},
+    scenario::{Acquisition, Error, Scenario, ScenarioResult},
+};
+
+// Makes it work more easily on WASM + other platforms
+const ASSETS: [(&str, &[u8]); 2] = [
+    ("S&P 500", include_bytes!("../data/sp500.csv")),
+    ("Gold", include_bytes!("../data/gold.csv")),
+];
+/// Fixed point for growth factors, so savings compound without going
+/// through a float
+const GROWTH_SCALE: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Deserialize)]
+//"Date","Close"
+struct CsvRecord {
+    #[serde(rename = "Date")]
+    date: NaiveDate,
+    #[serde(deserialize_with = "deserialize_close", rename = "Close")]
+    close: DollarAmount,
+}
+
+fn deserialize_close<'de, D>(deserializer: D) -> Result<DollarAmount, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let close: String = Deserialize::deserialize(deserializer)?;
+    close.parse().map_err(serde::de::Error::custom)
+}
+
+/// Month-end closing prices of an asset, for one unit of it: a share of the
+/// index, or a troy ounce of gold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Asset {
+    pub name: String,
+    closes: BTreeMap<NaiveDate, DollarAmount>,
+}
+
+impl Asset {
+    pub fn from_csv(name: &str, reader: impl std::io::Read) -> Result<Self, csv::Error> {
+        let mut reader = csv::Reader::from_reader(reader);
+        let mut closes = BTreeMap::new();
+        for record in reader.deserialize() {
+            let record: CsvRecord = record?;
+            closes.insert(record.date, record.close);
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            closes,
+        })
+    }
+
+    /// Price of a unit on `date`, interpolated linearly between the closes
+    /// around it. There's none outside of the history, rather than a stale
+    /// one.
+    fn price(&self, date: NaiveDate) -> Result<DollarAmount, Error> {
+        let missing = Error::MissingPrice(date);
+        let (&before_date, &before) = self.closes.range(..=date).next_back().ok_or(missing)?;
+        let (&after_date, &after) = self.closes.range(date..).next().ok_or(missing)?;
+        if after_date == before_date {
+            return Ok(before);
+        }
+
+        let elapsed = (date - before_date).num_days();
+        let span = (after_date - before_date).num_days();
+        let change = (after.cents() as i64 - before.cents() as i64) * elapsed / span;
+        u64::try_from(before.cents() as i64 + change)
+            .map(DollarAmount::from_cents)
+            .map_err(|_| Error::Overflow)
+    }
+}
+
+/// The bundled asset histories, the S&P 500 and gold, without those that
+/// can't be read
+pub fn load_assets() -> Vec<Asset> {
+    ASSETS
+        .into_iter()
+        .filter_map(|(name, csv)| Asset::from_csv(name, csv).ok())
+        .collect()
+}
+
+/// `1 + rate_bps / 365` to the power of `days`, in `GROWTH_SCALE`ths. `None`
+/// if it grows too large to compute.
+fn compound_daily(rate_bps: u64, days: u32) -> Option<u128> {
+    let multiply = |a: u128, b: u128| Some(a.checked_mul(b)? / GROWTH_SCALE);
+    let mut factor =
+        GROWTH_SCALE + GROWTH_SCALE * u128::from(rate_bps) / (u128::from(BASIS_POINTS) * 365);
+    let mut growth = GROWTH_SCALE;
+    let mut days = days;
+    while days > 0 {
+        if days & 1 == 1 {
+            growth = multiply(growth, factor)?;
+        }
+        days >>= 1;
+        if days > 0 {
+            factor = multiply(factor, factor)?;
+        }
+    }
+    Some(growth)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Benchmark<'a> {
+    /// Kept as it is
+    Cash,
+    /// Savings account compounding daily at a yearly rate, in basis points
+    Savings {
+        rate_bps: u64,
+    },
+    Asset(&'a Asset),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchmarkResult {
+    pub invested: DollarAmount,
+    pub value: DollarAmount,
+    pub gain: SignedDollarAmount,
+    /// `value / invested`, e.g. 2.0 if the value doubled
+    pub multiple: f64,
+}
+
+impl Benchmark<'_> {
+    /// Value on the evaluation date of one contribution made on `date`
+    fn grow(
+        &self,
+        amount: DollarAmount,
+        date: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<DollarAmount, Error> {
+        match self {
+            Benchmark::Cash => Ok(amount),
+            Benchmark::Savings { rate_bps } => {
+                let days = u32::try_from((until - date).num_days())
+                    .map_err(|_| Error::EvaluationBeforeBuy)?;
+                let growth = compound_daily(*rate_bps, days).ok_or(Error::Overflow)?;
+                let cents = (u128::from(amount.cents()) * growth + GROWTH_SCALE / 2) / GROWTH_SCALE;
+                u64::try_from(cents)
+                    .map(DollarAmount::from_cents)
+                    .map_err(|_| Error::Overflow)
+            }
+            Benchmark::Asset(asset) => mul_div(
+                amount.cents(),
+                asset.price(until)?.cents(),
+                asset.price(date)?.cents(),
+                Rounding::Nearest,
+            )
+            .map(DollarAmount::from_cents)
+            .ok_or(Error::Overflow),
+        }
+    }
+
+    /// The whole amount put in the benchmark on each date bitcoin was bought,
+    /// cash included.
+    pub fn evaluate(
+        &self,
+        scenario: &Scenario,
+        acquisition: &Acquisition,
+    ) -> Result<BenchmarkResult, Error> {
+        let mut value = DollarAmount::default();
+        for purchase in &acquisition.purchases {
+            let grown = self.grow(scenario.amount, purchase.date, scenario.evaluation_date)?;
+            value = value.checked_add(grown).ok_or(Error::Overflow)?;
+        }
+        let invested = acquisition.invested;
+
+        Ok(BenchmarkResult {
+            invested,
+            value,
+            gain: value.signed_sub(invested).ok_or(Error::Overflow)?,
+            multiple: value.ratio(invested).ok_or(Error::NothingInvested)?,
+        })
+    }
+
+    /// `result` with what wasn't converted into bitcoin put in the benchmark
+    /// on each purchase date, rather than kept in cash. Returns the blended
+    /// result, and what the benchmark part is worth.
+    pub fn hold_rest(
+        &self,
+        scenario: &Scenario,
+        result: &ScenarioResult,
+    ) -> Result<(ScenarioResult, DollarAmount), Error> {
+        let mut rest = DollarAmount::default();
+        for purchase in &result.acquisition.purchases {
+            let kept = scenario
+                .amount
+                .checked_sub(purchase.amount)
+                .ok_or(Error::InvalidAllocation)?;
+            let grown = self.grow(kept, purchase.date, scenario.evaluation_date)?;
+            rest = rest.checked_add(grown).ok_or(Error::Overflow)?;
+        }
+        let value = result
+            .bitcoin_value
+            .checked_add(rest)
+            .ok_or(Error::Overflow)?;
+        let invested = result.acquisition.invested;
+
+        let blended = ScenarioResult {
+            value,
+            gain: value.signed_sub(invested).ok_or(Error::Overflow)?,
+            multiple: value.ratio(invested).ok_or(Error::NothingInvested)?,
+            ..result.clone()
+        };
+        Ok((blended, rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fees::FeeModel,
+        price_lookup::test_prices::{date, history},
+        scenario::{Allocation, Frequency, Strategy},
+        tax::{CostBasisMethod, Rates, Sale, TaxRules},
+    };
+
+    #[test]
+    fn test_benchmarks() {
+        let prices = history(&[(date(2020, 1, 1), 10_000), (date(2021, 1, 1), 10_000)]);
+        let mut scenario = Scenario {
+            amount: DollarAmount::from(1_000),
+            allocation: Allocation::default(),
+            buy_date: date(2020, 1, 1),
+            evaluation_date: date(2021, 1, 1),
+            strategy: Strategy::LumpSum,
+            fees: FeeModel::default(),
+        };
+        let acquisition = scenario.acquire(&prices).unwrap();
+
+        let cash = Benchmark::Cash.evaluate(&scenario, &acquisition).unwrap();
+        assert_eq!(cash.value, DollarAmount::from(1_000));
+        assert_eq!(cash.multiple, 1.0);
+
+        // 366 days at 5%, compounded daily
+        let savings = Benchmark::Savings { rate_bps: 500 };
+        let result = savings.evaluate(&scenario, &acquisition).unwrap();
+        assert_eq!(result.value, DollarAmount::from_cents(105_141));
+        assert_eq!(result.gain.to_string(), "+$51.41");
+
+        let asset = Asset {
+            name: String::from("S&P 500"),
+            closes: BTreeMap::from([
+                (date(2020, 1, 1), DollarAmount::from(3_000)),
+                (date(2021, 1, 1), DollarAmount::from(3_750)),
+            ]),
+        };
+        // 182 of 366 days in
+        assert_eq!(
+            asset.price(date(2020, 7, 1)),
+            Ok(DollarAmount::from_cents(337_295))
+        );
+        let result = Benchmark::Asset(&asset)
+            .evaluate(&scenario, &acquisition)
+            .unwrap();
+        assert_eq!(result.value, DollarAmount::from(1_250));
+
+        // Each contribution grows from its own date
+        scenario.strategy = Strategy::DollarCostAveraging(Frequency::Monthly);
+        let acquisition = scenario.acquire(&prices).unwrap();
+        let result = Benchmark::Asset(&asset)
+            .evaluate(&scenario, &acquisition)
+            .unwrap();
+        assert_eq!(result.invested, DollarAmount::from(2_000));
+        assert_eq!(result.value, DollarAmount::from(2_250));
+
+        scenario.evaluation_date = date(2021, 2, 1);
+        assert_eq!(
+            Benchmark::Asset(&asset).evaluate(&scenario, &acquisition),
+            Err(Error::MissingPrice(date(2021, 2, 1)))
+        );
+    }
+
+    #[test]
+    fn test_holding_the_rest() {
+        // Bitcoin doubles, and the index goes up by half
+        let prices = history(&[(date(2020, 1, 1), 10_000), (date(2021, 1, 1), 20_000)]);
+        let asset = Asset {
+            name: String::from("S&P 500"),
+            closes: BTreeMap::from([
+                (date(2020, 1, 1), DollarAmount::from(3_000)),
+                (date(2021, 1, 1), DollarAmount::from(4_500)),
+            ]),
+        };
+        let scenario = Scenario {
+            amount: DollarAmount::from(1_000),
+            allocation: Allocation::Percent(10),
+            buy_date: date(2020, 1, 1),
+            evaluation_date: date(2021, 1, 1),
+            strategy: Strategy::LumpSum,
+            fees: FeeModel::default(),
+        };
+        let result = scenario.evaluate(&prices).unwrap();
+        assert_eq!(result.value, DollarAmount::from(1_100));
+
+        let (blended, rest) = Benchmark::Asset(&asset)
+            .hold_rest(&scenario, &result)
+            .unwrap();
+        assert_eq!(rest, DollarAmount::from(1_350));
+        assert_eq!(blended.value, DollarAmount::from(1_550));
+        assert_eq!(blended.gain.to_string(), "+$550");
+        assert_eq!(blended.bitcoin_value, DollarAmount::from(200));
+
+        // Only the bitcoin gain is taxed, out of the whole portfolio
+        let rules = TaxRules {
+            short_term: Rates::flat(3_000),
+            ..TaxRules::none()
+        };
+        let sale = Sale {
+            date: scenario.evaluation_date,
+            percent: 100,
+            method: CostBasisMethod::Fifo,
+        };
+        let estimate = rules.estimate(&blended, sale).unwrap();
+        assert_eq!(estimate.tax, DollarAmount::from(30));
+        assert_eq!(estimate.after_tax_value, DollarAmount::from(1_520));
+
+        let (blended, rest) = Benchmark::Cash.hold_rest(&scenario, &result).unwrap();
+        assert_eq!(rest, DollarAmount::from(900));
+        assert_eq!(blended, result);
+    }
+
+    #[test]
+    fn test_loading_assets() {
+        let assets = load_assets();
+        let names: Vec<&str> = assets.iter().map(|asset| asset.name.as_str()).collect();
+        assert_eq!(names, ["S&P 500", "Gold"]);
+
+        let sp500 = &assets[0];
+        assert_eq!(
+            sp500.price(date(2020, 12, 31)),
+            Ok(DollarAmount::from_cents(375_607))
+        );
+        assert!(sp500.price(date(2015, 6, 15)).is_ok());
+        assert!(sp500.price(date(2009, 12, 31)).is_err());
+    }
+
+    #[test]
+    fn test_compound_daily() {
+        assert_eq!(compound_daily(500, 0), Some(GROWTH_SCALE));
+        assert_eq!(compound_daily(0, 10_000), Some(GROWTH_SCALE));
+        // 5% a year compounded daily for a year is a bit more than 5%
+        let growth = compound_daily(500, 365).unwrap();
+        assert_eq!(growth * 100_000 / GROWTH_SCALE, 105_126);
+        // Too large a factor to compute
+        assert_eq!(compound_daily(10_000, 365 * 100), None);
+    }
+}
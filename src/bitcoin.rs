@@ -1,21 +1,47 @@
 //! Human readable Bitcoin amounts, following some of the guidelines from
 //! https://bitcoin.design/guide/designing-products/units-and-symbols/
-use std::fmt::Display;
+//!
+//! Amounts are serialized as an integer number of sats, e.g. `123456`.
+use std::{fmt::Display, iter::Sum};
+
+use serde::{Deserialize, Serialize};
+
+use crate::price::{mul_div, Rounding};
 
 /// Minimum amount of sats to display the ₿ symbol
 const B_DISPLAY_THRESHOLD: u64 = 1_000_000;
 const SATS_IN_BTC: u64 = 100_000_000;
+const MSATS_IN_SAT: u64 = 1_000;
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct BitcoinAmount {
     sats: u64,
 }
 
-// TODO:
-// https://bitcoin.design/guide/designing-products/units-and-symbols/
-// Separate alternate coloring, use a monospace font
+/// Amount with a precision of a thousandth of a sat, like Lightning payments.
+/// Useful so small amounts don't get rounded to 0 sats, e.g. $1 at $60k is
+/// 1 666.666 sats.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MilliSatAmount {
+    msats: u64,
+}
+
+/// Difference between two `BitcoinAmount`s, e.g. a gain or a loss.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SignedBitcoinAmount {
+    sats: i64,
+}
+
 impl BitcoinAmount {
-    pub fn from(sats: u64) -> Self {
+    /// There will never be more than 21 million bitcoin.
+    pub const MAX_SUPPLY: BitcoinAmount = BitcoinAmount {
+        sats: 21_000_000 * SATS_IN_BTC,
+    };
+
+    pub const fn from(sats: u64) -> Self {
         Self { sats }
     }
 
@@ -27,6 +53,51 @@ impl BitcoinAmount {
         self.sats
     }
 
+    /// Whether this amount could actually exist, i.e. isn't over the 21M cap.
+    pub fn is_valid(&self) -> bool {
+        self.sats <= Self::MAX_SUPPLY.sats
+    }
+
+    pub fn checked_add(self, rhs: BitcoinAmount) -> Option<BitcoinAmount> {
+        self.sats.checked_add(rhs.sats).map(BitcoinAmount::from)
+    }
+
+    pub fn checked_sub(self, rhs: BitcoinAmount) -> Option<BitcoinAmount> {
+        self.sats.checked_sub(rhs.sats).map(BitcoinAmount::from)
+    }
+
+    pub fn checked_mul(self, rhs: u64) -> Option<BitcoinAmount> {
+        self.sats.checked_mul(rhs).map(BitcoinAmount::from)
+    }
+
+    /// Rounded down to the sat.
+    pub fn checked_div(self, rhs: u64) -> Option<BitcoinAmount> {
+        self.sats.checked_div(rhs).map(BitcoinAmount::from)
+    }
+
+    /// `None` if the amount is too large to be counted in msats, which can
+    /// only happen over the 21M cap.
+    pub fn to_msats(self) -> Option<MilliSatAmount> {
+        self.sats
+            .checked_mul(MSATS_IN_SAT)
+            .map(MilliSatAmount::from_msats)
+    }
+
+    /// `self - rhs`, which can be negative.
+    pub fn signed_sub(self, rhs: BitcoinAmount) -> Option<SignedBitcoinAmount> {
+        let lhs = SignedBitcoinAmount::try_from(self).ok()?;
+        let rhs = SignedBitcoinAmount::try_from(rhs).ok()?;
+        lhs.checked_sub(rhs)
+    }
+
+    /// Displays the amount with the given format instead of the default one.
+    pub fn display(self, format: BitcoinFormat) -> FormattedBitcoinAmount {
+        FormattedBitcoinAmount {
+            amount: self,
+            format,
+        }
+    }
+
     fn split_btc_sats(&self) -> (u64, u64) {
         let sats = self.sats % SATS_IN_BTC;
         let remaining_btc = self.sats / SATS_IN_BTC;
@@ -35,55 +106,329 @@ impl BitcoinAmount {
     }
 }
 
+/// `None` if the total overflows.
+impl Sum<BitcoinAmount> for Option<BitcoinAmount> {
+    fn sum<I: Iterator<Item = BitcoinAmount>>(mut iter: I) -> Self {
+        iter.try_fold(BitcoinAmount::default(), BitcoinAmount::checked_add)
+    }
+}
+
+impl MilliSatAmount {
+    pub fn from_msats(msats: u64) -> Self {
+        Self { msats }
+    }
+
+    pub fn msats(&self) -> u64 {
+        self.msats
+    }
+
+    /// Whole sats, e.g. to actually buy or send this amount.
+    pub fn to_sats(self, rounding: Rounding) -> BitcoinAmount {
+        let sats = mul_div(self.msats, 1, MSATS_IN_SAT, rounding);
+        BitcoinAmount::from(sats.expect("Dividing by a constant fits in a u64"))
+    }
+
+    pub fn checked_add(self, rhs: MilliSatAmount) -> Option<MilliSatAmount> {
+        self.msats
+            .checked_add(rhs.msats)
+            .map(MilliSatAmount::from_msats)
+    }
+
+    pub fn checked_sub(self, rhs: MilliSatAmount) -> Option<MilliSatAmount> {
+        self.msats
+            .checked_sub(rhs.msats)
+            .map(MilliSatAmount::from_msats)
+    }
+
+    pub fn segments(&self) -> Vec<Segment> {
+        segments(&self.to_string())
+    }
+}
+
+/// `None` if the total overflows.
+impl Sum<MilliSatAmount> for Option<MilliSatAmount> {
+    fn sum<I: Iterator<Item = MilliSatAmount>>(mut iter: I) -> Self {
+        iter.try_fold(MilliSatAmount::default(), MilliSatAmount::checked_add)
+    }
+}
+
+/// Always in sats, with the msats as decimals: 1 234.567 sats
+impl Display for MilliSatAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sats = group_thousands(self.msats / MSATS_IN_SAT);
+        let msats = self.msats % MSATS_IN_SAT;
+
+        write!(f, "{sats}.{msats:03} sats")
+    }
+}
+
+impl SignedBitcoinAmount {
+    pub fn from(sats: i64) -> Self {
+        Self { sats }
+    }
+
+    pub fn sats(&self) -> i64 {
+        self.sats
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.sats < 0
+    }
+
+    pub fn abs(self) -> BitcoinAmount {
+        BitcoinAmount::from(self.sats.unsigned_abs())
+    }
+
+    pub fn checked_add(self, rhs: SignedBitcoinAmount) -> Option<SignedBitcoinAmount> {
+        self.sats
+            .checked_add(rhs.sats)
+            .map(SignedBitcoinAmount::from)
+    }
+
+    pub fn checked_sub(self, rhs: SignedBitcoinAmount) -> Option<SignedBitcoinAmount> {
+        self.sats
+            .checked_sub(rhs.sats)
+            .map(SignedBitcoinAmount::from)
+    }
+}
+
+impl TryFrom<BitcoinAmount> for SignedBitcoinAmount {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(amount: BitcoinAmount) -> Result<Self, Self::Error> {
+        i64::try_from(amount.sats).map(SignedBitcoinAmount::from)
+    }
+}
+
+/// Same as `BitcoinAmount`, with a sign: +₿0.01 000 000, -999 sats
+impl Display for SignedBitcoinAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = match self.sats.signum() {
+            -1 => "-",
+            1 => "+",
+            _ => "",
+        };
+
+        write!(f, "{sign}{}", self.abs())
+    }
+}
+
+/// Units an amount can be displayed in.
+/// See https://bitcoin.design/guide/designing-products/units-and-symbols/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BitcoinUnit {
+    /// sats for small amounts, ₿ from the format's threshold
+    #[default]
+    Auto,
+    /// ₿0.01 000 000
+    Btc,
+    /// 10.00 000 mBTC
+    MilliBtc,
+    /// 10 000.00 bits, also known as μBTC
+    Bits,
+    /// 1 000 000 sats
+    Sats,
+    /// ₿1 000 000: the ₿ symbol used for integer sats amounts
+    IntegerBtc,
+}
+
+impl BitcoinUnit {
+    pub const ALL: [BitcoinUnit; 6] = [
+        BitcoinUnit::Auto,
+        BitcoinUnit::Btc,
+        BitcoinUnit::MilliBtc,
+        BitcoinUnit::Bits,
+        BitcoinUnit::Sats,
+        BitcoinUnit::IntegerBtc,
+    ];
+
+    /// How many sats one of this unit is worth, and so how many decimals it
+    /// needs to represent every sat.
+    fn sats_and_decimals(self) -> (u64, usize) {
+        match self {
+            BitcoinUnit::Auto | BitcoinUnit::Btc => (SATS_IN_BTC, 8),
+            BitcoinUnit::MilliBtc => (100_000, 5),
+            BitcoinUnit::Bits => (100, 2),
+            BitcoinUnit::Sats | BitcoinUnit::IntegerBtc => (1, 0),
+        }
+    }
+}
+
+/// How to display a `BitcoinAmount`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitcoinFormat {
+    pub unit: BitcoinUnit,
+    /// Minimum amount of sats to switch from sats to ₿ in `BitcoinUnit::Auto`
+    pub btc_threshold: u64,
+}
+
+impl Default for BitcoinFormat {
+    fn default() -> Self {
+        Self {
+            unit: BitcoinUnit::default(),
+            btc_threshold: B_DISPLAY_THRESHOLD,
+        }
+    }
+}
+
+impl BitcoinFormat {
+    /// Resolves `BitcoinUnit::Auto` into the unit to use for this amount.
+    fn unit_for(&self, amount: BitcoinAmount) -> BitcoinUnit {
+        match self.unit {
+            BitcoinUnit::Auto if amount.sats < self.btc_threshold => BitcoinUnit::Sats,
+            BitcoinUnit::Auto => BitcoinUnit::Btc,
+            unit => unit,
+        }
+    }
+}
+
+/// A `BitcoinAmount` along with the format to display it with.
+pub struct FormattedBitcoinAmount {
+    amount: BitcoinAmount,
+    format: BitcoinFormat,
+}
+
+/// What part of a displayed amount a piece of text is, so it can be styled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentKind {
+    /// ₿ symbol, or unit suffix like " sats"
+    Unit,
+    /// Zeros before the first significant digit, which should be dimmed
+    LeadingZeros,
+    /// A group of digits. Every other group is `alternate`, counting from the
+    /// right, so the sats group always has the same style.
+    Digits { alternate: bool },
+    /// Thousands separator or decimal point
+    Separator,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub text: String,
+    pub kind: SegmentKind,
+}
+
+impl FormattedBitcoinAmount {
+    /// Splits the displayed amount into pieces that can be styled separately,
+    /// e.g. to alternate digit group colours as recommended by bitcoin.design.
+    pub fn segments(&self) -> Vec<Segment> {
+        segments(&self.to_string())
+    }
+}
+
+fn segments(displayed: &str) -> Vec<Segment> {
+    let is_digit = |c: char| c.is_ascii_digit();
+    let (Some(first_digit), Some(last_digit)) =
+        (displayed.find(is_digit), displayed.rfind(is_digit))
+    else {
+        return vec![];
+    };
+    let (prefix, rest) = displayed.split_at(first_digit);
+    let (digits, suffix) = rest.split_at(last_digit - first_digit + 1);
+
+    let mut segments = vec![];
+    let mut push = |text: &str, kind| {
+        if !text.is_empty() {
+            segments.push(Segment {
+                text: text.to_string(),
+                kind,
+            })
+        }
+    };
+
+    push(prefix, SegmentKind::Unit);
+
+    let groups: Vec<&str> = digits.split([' ', '.']).collect();
+    let mut separators = digits.matches([' ', '.']);
+    let mut leading = true;
+    for (index, group) in groups.iter().enumerate() {
+        let significant = if leading {
+            let start = group.find(|c| c != '0').unwrap_or(group.len());
+            push(&group[..start], SegmentKind::LeadingZeros);
+            leading = start == group.len();
+            &group[start..]
+        } else {
+            group
+        };
+
+        let alternate = (groups.len() - index).is_multiple_of(2);
+        push(significant, SegmentKind::Digits { alternate });
+
+        if let Some(separator) = separators.next() {
+            push(separator, SegmentKind::Separator);
+        }
+    }
+
+    push(suffix, SegmentKind::Unit);
+
+    segments
+}
+
 fn separate_thousands(amount: u64) -> Vec<u64> {
     let mut remainder = amount;
     let mut acc = vec![];
     while remainder > 0 {
         acc.push(remainder % 1000);
-        remainder = remainder / 1000;
+        remainder /= 1000;
     }
 
     acc.reverse();
     acc
 }
 
+/// Example: 1 234 567. The first thousands block isn't padded.
+fn group_thousands(amount: u64) -> String {
+    match separate_thousands(amount).split_first() {
+        None => 0.to_string(),
+        Some((head, tail)) => std::iter::once(head.to_string())
+            .chain(tail.iter().map(|thousand| format!("{thousand:03}")))
+            .collect::<Vec<String>>()
+            .join(" "),
+    }
+}
+
+/// Pads the decimals, and groups them by thousands starting from the right so
+/// the last block always lines up with sats. Example: 01 000 000
+fn group_decimals(decimals: u64, width: usize) -> String {
+    let mut decimals = format!("{decimals:0width$}");
+    let mut position = width;
+    while position > 3 {
+        position -= 3;
+        decimals.insert(position, ' ');
+    }
+
+    decimals
+}
+
+impl Display for FormattedBitcoinAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unit = self.format.unit_for(self.amount);
+        let (sats_in_unit, decimals) = unit.sats_and_decimals();
+        let (whole, fraction) = match unit {
+            BitcoinUnit::Auto | BitcoinUnit::Btc => self.amount.split_btc_sats(),
+            _ => (
+                self.amount.sats / sats_in_unit,
+                self.amount.sats % sats_in_unit,
+            ),
+        };
+        let whole = group_thousands(whole);
+        let fraction = group_decimals(fraction, decimals);
+
+        match unit {
+            BitcoinUnit::Auto | BitcoinUnit::Btc => write!(f, "₿{whole}.{fraction}"),
+            BitcoinUnit::MilliBtc => write!(f, "{whole}.{fraction} mBTC"),
+            BitcoinUnit::Bits => write!(f, "{whole}.{fraction} bits"),
+            BitcoinUnit::Sats => write!(f, "{whole} sats"),
+            BitcoinUnit::IntegerBtc => write!(f, "₿{whole}"),
+        }
+    }
+}
+
 /// Under 1M sats -> sats only.
 /// Else: ₿ with sats thousands.
 impl Display for BitcoinAmount {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.sats < B_DISPLAY_THRESHOLD {
-            let sats = separate_thousands(self.sats)
-                .iter()
-                .map(|v| format!("{v:03}"))
-                .collect::<Vec<String>>()
-                .join(" ");
-            let sats = sats.trim_start_matches("0");
-
-            write!(f, "{sats} sats")
-        } else {
-            let (btc, sats) = self.split_btc_sats();
-
-            let btc_thousands = separate_thousands(btc);
-            let btc = match btc_thousands.len() {
-                0 => 0.to_string(),
-                1 => btc_thousands[0].to_string(),
-                _ => {
-                    // Don't pad the first thousands block
-                    let (head, tail) = btc_thousands.split_at(1);
-                    head.iter()
-                        .map(ToString::to_string)
-                        .chain(tail.iter().map(|thousand| format!("{thousand:03}")))
-                        .collect::<Vec<String>>()
-                        .join(" ")
-                }
-            };
-
-            // Example: 10 000 000
-            let mut sats = format!("{sats:08}");
-            sats.insert(5, ' ');
-            sats.insert(2, ' ');
-            write!(f, "₿{btc}.{sats}")
-        }
+        self.display(BitcoinFormat::default()).fmt(f)
     }
 }
 
@@ -158,4 +503,195 @@ mod tests {
         assert_eq!(BitcoinAmount::from(10_000).to_string(), "10 000 sats");
         assert_eq!(BitcoinAmount::from(2_000).to_string(), "2 000 sats");
     }
+
+    #[test]
+    fn test_display_units() {
+        let amount = BitcoinAmount::from(123_456_789_000);
+        let display = |unit| {
+            amount
+                .display(BitcoinFormat {
+                    unit,
+                    ..Default::default()
+                })
+                .to_string()
+        };
+
+        assert_eq!(display(BitcoinUnit::Auto), "₿1 234.56 789 000");
+        assert_eq!(display(BitcoinUnit::Btc), "₿1 234.56 789 000");
+        assert_eq!(display(BitcoinUnit::MilliBtc), "1 234 567.89 000 mBTC");
+        assert_eq!(display(BitcoinUnit::Bits), "1 234 567 890.00 bits");
+        assert_eq!(display(BitcoinUnit::Sats), "123 456 789 000 sats");
+        assert_eq!(display(BitcoinUnit::IntegerBtc), "₿123 456 789 000");
+
+        let small = BitcoinAmount::from(1_234);
+        let btc = BitcoinFormat {
+            unit: BitcoinUnit::Btc,
+            ..Default::default()
+        };
+        assert_eq!(small.display(btc).to_string(), "₿0.00 001 234");
+        assert_eq!(BitcoinAmount::from(0).to_string(), "0 sats");
+    }
+
+    #[test]
+    fn test_display_threshold() {
+        let format = BitcoinFormat {
+            unit: BitcoinUnit::Auto,
+            btc_threshold: 10_000,
+        };
+
+        assert_eq!(
+            BitcoinAmount::from(9_999).display(format).to_string(),
+            "9 999 sats"
+        );
+        assert_eq!(
+            BitcoinAmount::from(10_000).display(format).to_string(),
+            "₿0.00 010 000"
+        );
+    }
+
+    #[test]
+    fn test_segments() {
+        let segment = |text: &str, kind| Segment {
+            text: text.to_string(),
+            kind,
+        };
+        let digits = |text, alternate| segment(text, SegmentKind::Digits { alternate });
+
+        assert_eq!(
+            BitcoinAmount::from(1_234_000)
+                .display(BitcoinFormat::default())
+                .segments(),
+            vec![
+                segment("₿", SegmentKind::Unit),
+                segment("0", SegmentKind::LeadingZeros),
+                segment(".", SegmentKind::Separator),
+                segment("0", SegmentKind::LeadingZeros),
+                digits("1", false),
+                segment(" ", SegmentKind::Separator),
+                digits("234", true),
+                segment(" ", SegmentKind::Separator),
+                digits("000", false),
+            ]
+        );
+
+        assert_eq!(
+            BitcoinAmount::from(12_345)
+                .display(BitcoinFormat::default())
+                .segments(),
+            vec![
+                digits("12", true),
+                segment(" ", SegmentKind::Separator),
+                digits("345", false),
+                segment(" sats", SegmentKind::Unit),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let one = BitcoinAmount::one_btc();
+        let sats = BitcoinAmount::from(1_000);
+
+        assert_eq!(
+            one.checked_add(sats),
+            Some(BitcoinAmount::from(100_001_000))
+        );
+        assert_eq!(one.checked_sub(sats), Some(BitcoinAmount::from(99_999_000)));
+        assert_eq!(sats.checked_sub(one), None);
+        assert_eq!(sats.checked_mul(3), Some(BitcoinAmount::from(3_000)));
+        assert_eq!(BitcoinAmount::from(u64::MAX).checked_mul(2), None);
+        assert_eq!(sats.checked_div(3), Some(BitcoinAmount::from(333)));
+        assert_eq!(sats.checked_div(0), None);
+
+        assert_eq!(
+            [one, sats, sats].into_iter().sum::<Option<BitcoinAmount>>(),
+            Some(BitcoinAmount::from(100_002_000))
+        );
+        assert_eq!(
+            [BitcoinAmount::from(u64::MAX), sats]
+                .into_iter()
+                .sum::<Option<BitcoinAmount>>(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_signed_amounts() {
+        let one = BitcoinAmount::one_btc();
+        let sats = BitcoinAmount::from(999);
+
+        let loss = sats.signed_sub(one).unwrap();
+        assert!(loss.is_negative());
+        assert_eq!(loss.sats(), -99_999_001);
+        assert_eq!(loss.abs(), BitcoinAmount::from(99_999_001));
+        assert_eq!(loss.to_string(), "-₿0.99 999 001");
+
+        let gain = one.signed_sub(sats).unwrap();
+        assert_eq!(gain.to_string(), "+₿0.99 999 001");
+        assert_eq!(gain.checked_add(loss), Some(SignedBitcoinAmount::default()));
+        assert_eq!(SignedBitcoinAmount::default().to_string(), "0 sats");
+
+        assert_eq!(BitcoinAmount::from(u64::MAX).signed_sub(one), None);
+    }
+
+    #[test]
+    fn test_max_supply() {
+        assert!(BitcoinAmount::MAX_SUPPLY.is_valid());
+        assert!(BitcoinAmount::one_btc().is_valid());
+        assert!(!BitcoinAmount::MAX_SUPPLY
+            .checked_add(BitcoinAmount::from(1))
+            .unwrap()
+            .is_valid());
+    }
+
+    #[test]
+    fn test_wire_format() {
+        let amount = BitcoinAmount::from(123_456);
+        assert_eq!(serde_json::to_string(&amount).unwrap(), "123456");
+        assert_eq!(
+            serde_json::from_str::<BitcoinAmount>("123456").unwrap(),
+            amount
+        );
+        assert!(serde_json::from_str::<BitcoinAmount>("-1").is_err());
+        assert!(serde_json::from_str::<BitcoinAmount>("\"123456\"").is_err());
+
+        let loss = SignedBitcoinAmount::from(-42);
+        assert_eq!(serde_json::to_string(&loss).unwrap(), "-42");
+        assert_eq!(
+            serde_json::from_str::<SignedBitcoinAmount>("-42").unwrap(),
+            loss
+        );
+    }
+
+    #[test]
+    fn test_msats() {
+        let msats = MilliSatAmount::from_msats(1_234_567);
+        assert_eq!(msats.to_string(), "1 234.567 sats");
+        assert_eq!(MilliSatAmount::from_msats(42).to_string(), "0.042 sats");
+
+        assert_eq!(msats.to_sats(Rounding::Down), BitcoinAmount::from(1_234));
+        assert_eq!(msats.to_sats(Rounding::Nearest), BitcoinAmount::from(1_235));
+        assert_eq!(msats.to_sats(Rounding::Up), BitcoinAmount::from(1_235));
+
+        assert_eq!(
+            BitcoinAmount::from(1_234).to_msats(),
+            Some(MilliSatAmount::from_msats(1_234_000))
+        );
+        assert_eq!(BitcoinAmount::from(u64::MAX).to_msats(), None);
+        assert!(BitcoinAmount::MAX_SUPPLY.to_msats().is_some());
+
+        // Small amounts add up instead of each being rounded down
+        let total = std::iter::repeat_n(MilliSatAmount::from_msats(1_500), 4)
+            .sum::<Option<MilliSatAmount>>()
+            .unwrap();
+        assert_eq!(total.to_sats(Rounding::Down), BitcoinAmount::from(6));
+
+        assert_eq!(
+            msats.segments().last(),
+            Some(&Segment {
+                text: " sats".to_string(),
+                kind: SegmentKind::Unit
+            })
+        );
+    }
 }
@@ -0,0 +1,44 @@
+//! Renders Bitcoin amounts in `iced` as recommended by
+//! https://bitcoin.design/guide/designing-products/units-and-symbols/:
+//! monospace digits, dimmed leading zeros and alternate colouring of digit
+//! groups.
+
+use iced::{
+    widget::{text, Row},
+    Color, Element, Font, Theme,
+};
+
+use crate::bitcoin::{Segment, SegmentKind};
+
+const DIMMED_ALPHA: f32 = 0.35;
+const ALTERNATE_ALPHA: f32 = 0.7;
+
+pub fn bitcoin_text<'a, Message: 'a>(
+    segments: Vec<Segment>,
+    size: u16,
+    theme: &Theme,
+) -> Element<'a, Message> {
+    let palette = theme.palette();
+    let with_alpha = |color: Color, a| Color { a, ..color };
+
+    segments
+        .into_iter()
+        .fold(Row::new(), |row, segment| {
+            let color = match segment.kind {
+                SegmentKind::LeadingZeros => with_alpha(palette.text, DIMMED_ALPHA),
+                SegmentKind::Digits { alternate: true } => {
+                    with_alpha(palette.text, ALTERNATE_ALPHA)
+                }
+                SegmentKind::Unit => palette.primary,
+                SegmentKind::Digits { alternate: false } | SegmentKind::Separator => palette.text,
+            };
+
+            row.push(
+                text(segment.text)
+                    .font(Font::MONOSPACE)
+                    .size(size)
+                    .style(color),
+            )
+        })
+        .into()
+}
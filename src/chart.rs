@@ -0,0 +1,409 @@
+//! Line charts of daily dollar amounts for `iced`, drawn on a `canvas`, with
+//! vertical markers on some dates and the closest value shown on hover.
+
+use chrono::{Days, NaiveDate};
+use iced::{
+    alignment,
+    mouse::Cursor,
+    theme::Palette,
+    widget::canvas::{self, Cache, Frame, Geometry, LineDash, Path, Stroke},
+    Color, Element, Length, Pixels, Point, Rectangle, Renderer, Theme,
+};
+
+use crate::{
+    dollar::DollarAmount,
+    i18n::{Language, Msg},
+};
+
+const HEIGHT: f32 = 250.0;
+/// Room for the dates under the plot and the labels above it
+const MARGIN: f32 = 20.0;
+const LABEL_SIZE: f32 = 12.0;
+/// Rough width of a label character, to lay out the legend
+const LABEL_CHAR_WIDTH: f32 = LABEL_SIZE * 0.6;
+const DASH: [f32; 2] = [6.0, 4.0];
+
+/// One line of the chart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Series {
+    pub label: String,
+    /// Sorted by date
+    pub points: Vec<(NaiveDate, DollarAmount)>,
+}
+
+/// A date to highlight, e.g. when bitcoin was bought
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Marker {
+    pub date: NaiveDate,
+    pub label: String,
+    /// Drawn in the theme's success colour, or its danger colour
+    pub positive: bool,
+}
+
+/// The first series is the main one, shown on hover. The others are dashed
+/// references, e.g. what was invested.
+pub struct LineChart {
+    series: Vec<Series>,
+    markers: Vec<Marker>,
+    log_scale: bool,
+    /// Writes the dates and the hovered amount
+    language: Language,
+    /// Everything but what's hovered, which is redrawn as the cursor moves
+    lines: Cache,
+}
+
+pub fn line_chart<'a, Message: 'a>(
+    series: Vec<Series>,
+    markers: Vec<Marker>,
+    log_scale: bool,
+    language: Language,
+) -> Element<'a, Message> {
+    canvas::Canvas::new(LineChart {
+        series,
+        markers,
+        log_scale,
+        language,
+        lines: Cache::new(),
+    })
+    .width(Length::Fill)
+    .height(HEIGHT)
+    .into()
+}
+
+/// Converts dates and amounts to positions in the plot
+struct Scale {
+    first_date: NaiveDate,
+    days: f32,
+    low: f32,
+    high: f32,
+    log_scale: bool,
+    bounds: Rectangle,
+}
+
+impl Scale {
+    fn transform(&self, amount: DollarAmount) -> f32 {
+        let dollars = amount.cents() as f32 / 100.0;
+        match self.log_scale {
+            // Nothing is worth less than a cent
+            true => dollars.max(0.01).ln(),
+            false => dollars,
+        }
+    }
+
+    fn x(&self, date: NaiveDate) -> f32 {
+        let elapsed = (date - self.first_date).num_days() as f32;
+        match self.days > 0.0 {
+            true => self.bounds.x + elapsed / self.days * self.bounds.width,
+            false => self.bounds.center_x(),
+        }
+    }
+
+    fn y(&self, amount: DollarAmount) -> f32 {
+        let range = self.high - self.low;
+        let position = match range > 0.0 {
+            true => (self.transform(amount) - self.low) / range,
+            false => 0.5,
+        };
+        self.bounds.y + (1.0 - position) * self.bounds.height
+    }
+
+    fn point(&self, date: NaiveDate, amount: DollarAmount) -> Point {
+        Point::new(self.x(date), self.y(amount))
+    }
+
+    /// Date under an horizontal position
+    fn date(&self, x: f32) -> NaiveDate {
+        let fraction = ((x - self.bounds.x) / self.bounds.width).clamp(0.0, 1.0);
+        let days = (fraction * self.days).round() as u64;
+        self.first_date
+            .checked_add_days(Days::new(days))
+            .unwrap_or(self.first_date)
+    }
+}
+
+impl LineChart {
+    fn points(&self) -> &[(NaiveDate, DollarAmount)] {
+        self.series
+            .first()
+            .map(|series| series.points.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn scale(&self, bounds: Rectangle) -> Option<Scale> {
+        let (first_date, _) = self.points().first()?;
+        let (last_date, _) = self.points().last()?;
+        let mut scale = Scale {
+            first_date: *first_date,
+            days: (*last_date - *first_date).num_days() as f32,
+            low: 0.0,
+            high: 0.0,
+            log_scale: self.log_scale,
+            bounds: Rectangle {
+                x: 0.0,
+                y: MARGIN,
+                width: bounds.width,
+                height: bounds.height - 2.0 * MARGIN,
+            },
+        };
+        let values: Vec<f32> = self
+            .series
+            .iter()
+            .flat_map(|series| &series.points)
+            .map(|(_, amount)| scale.transform(*amount))
+            .collect();
+        scale.low = values.iter().copied().fold(f32::INFINITY, f32::min);
+        scale.high = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+
+        Some(scale)
+    }
+
+    /// The lines, markers, dates and legend, which only change with the
+    /// chart's size
+    fn draw_lines(&self, frame: &mut Frame, scale: &Scale, palette: Palette) {
+        let dimmed = dimmed(palette);
+        let colors = [
+            palette.primary,
+            palette.text,
+            palette.success,
+            palette.danger,
+        ];
+        // References first, so the main line is drawn over them
+        for (i, series) in self.series.iter().enumerate().rev() {
+            let line = Path::new(|builder| {
+                for (j, (date, amount)) in series.points.iter().enumerate() {
+                    match j {
+                        0 => builder.move_to(scale.point(*date, *amount)),
+                        _ => builder.line_to(scale.point(*date, *amount)),
+                    }
+                }
+            });
+            let stroke = Stroke::default().with_color(colors[i % colors.len()]);
+            frame.stroke(
+                &line,
+                match i {
+                    0 => stroke.with_width(2.0),
+                    _ => Stroke {
+                        line_dash: LineDash {
+                            segments: &DASH,
+                            offset: 0,
+                        },
+                        ..stroke.with_width(1.0)
+                    },
+                },
+            );
+        }
+
+        for marker in &self.markers {
+            let color = match marker.positive {
+                true => palette.success,
+                false => palette.danger,
+            };
+            let x = scale.x(marker.date);
+            frame.stroke(
+                &Path::line(
+                    Point::new(x, scale.bounds.y),
+                    Point::new(x, scale.bounds.y + scale.bounds.height),
+                ),
+                Stroke::default().with_color(color).with_width(1.0),
+            );
+            frame.fill_text(canvas::Text {
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Bottom,
+                ..label(
+                    marker.label.to_string(),
+                    Point::new(x, scale.bounds.y),
+                    color,
+                )
+            });
+        }
+
+        // Range of the chart
+        let points = self.points();
+        let (first_date, _) = points[0];
+        let (last_date, _) = points[points.len() - 1];
+        let bottom = scale.bounds.y + scale.bounds.height;
+        frame.fill_text(label(
+            self.language.date(first_date),
+            Point::new(0.0, bottom + 4.0),
+            dimmed,
+        ));
+        frame.fill_text(canvas::Text {
+            horizontal_alignment: alignment::Horizontal::Right,
+            ..label(
+                self.language.date(last_date),
+                Point::new(scale.bounds.width, bottom + 4.0),
+                dimmed,
+            )
+        });
+
+        if self.series.len() > 1 {
+            let labels: Vec<String> = self
+                .series
+                .iter()
+                .map(|series| format!("— {}", series.label))
+                .collect();
+            let spacing = 2.0 * LABEL_CHAR_WIDTH;
+            let width = |label: &String| label.chars().count() as f32 * LABEL_CHAR_WIDTH;
+            let total = labels
+                .iter()
+                .map(|label| width(label) + spacing)
+                .sum::<f32>();
+            let mut x = (scale.bounds.width - total) / 2.0;
+            for (i, label_text) in labels.into_iter().enumerate() {
+                let next = x + width(&label_text) + spacing;
+                frame.fill_text(label(
+                    label_text,
+                    Point::new(x, bottom + 4.0),
+                    colors[i % colors.len()],
+                ));
+                x = next;
+            }
+        }
+    }
+
+    /// Point with the date closest to `date`
+    fn closest(&self, date: NaiveDate) -> Option<(NaiveDate, DollarAmount)> {
+        let points = self.points();
+        let index = points.partition_point(|(point, _)| *point < date);
+        let after = points.get(index);
+        let before = index.checked_sub(1).and_then(|index| points.get(index));
+        match (before, after) {
+            (Some(before), Some(after)) if date - before.0 < after.0 - date => Some(*before),
+            (_, Some(after)) => Some(*after),
+            (before, None) => before.copied(),
+        }
+    }
+}
+
+fn dimmed(palette: Palette) -> Color {
+    Color {
+        a: 0.5,
+        ..palette.text
+    }
+}
+
+fn label(content: String, position: Point, color: Color) -> canvas::Text {
+    canvas::Text {
+        content,
+        position,
+        color,
+        size: Pixels(LABEL_SIZE),
+        ..canvas::Text::default()
+    }
+}
+
+impl<Message> canvas::Program<Message> for LineChart {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let Some(scale) = self.scale(bounds) else {
+            return vec![frame.into_geometry()];
+        };
+        let palette = theme.palette();
+        let dimmed = dimmed(palette);
+        let lines = self.lines.draw(renderer, bounds.size(), |frame| {
+            self.draw_lines(frame, &scale, palette)
+        });
+
+        let bottom = scale.bounds.y + scale.bounds.height;
+        let hovered = cursor
+            .position_in(bounds)
+            .and_then(|position| self.closest(scale.date(position.x)));
+        if let Some((date, amount)) = hovered {
+            let point = scale.point(date, amount);
+            frame.stroke(
+                &Path::line(
+                    Point::new(point.x, scale.bounds.y),
+                    Point::new(point.x, bottom),
+                ),
+                Stroke::default().with_color(dimmed).with_width(1.0),
+            );
+            frame.fill(&Path::circle(point, 4.0), palette.primary);
+            // Keeps the label inside the chart
+            let horizontal_alignment = match point.x > bounds.width / 2.0 {
+                true => alignment::Horizontal::Right,
+                false => alignment::Horizontal::Left,
+            };
+            let offset = match horizontal_alignment {
+                alignment::Horizontal::Right => -8.0,
+                _ => 8.0,
+            };
+            frame.fill_text(canvas::Text {
+                horizontal_alignment,
+                vertical_alignment: alignment::Vertical::Center,
+                ..label(
+                    self.language.tr(Msg::ChartPoint(date, amount)),
+                    Point::new(point.x + offset, point.y),
+                    palette.text,
+                )
+            });
+        }
+
+        vec![lines, frame.into_geometry()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price_lookup::test_prices::date;
+
+    #[test]
+    fn test_scale() {
+        let chart = LineChart {
+            series: vec![
+                Series {
+                    label: String::from("Price"),
+                    points: vec![
+                        (date(2020, 1, 1), DollarAmount::from(10)),
+                        (date(2020, 1, 3), DollarAmount::from(1_000)),
+                        (date(2020, 1, 11), DollarAmount::from(100)),
+                    ],
+                },
+                Series {
+                    label: String::from("Reference"),
+                    points: vec![(date(2020, 1, 1), DollarAmount::from(100))],
+                },
+            ],
+            markers: vec![],
+            log_scale: false,
+            language: Language::English,
+            lines: Cache::new(),
+        };
+        let bounds = Rectangle::new(Point::ORIGIN, iced::Size::new(100.0, 100.0 + 2.0 * MARGIN));
+        let scale = chart.scale(bounds).unwrap();
+        assert_eq!(scale.x(date(2020, 1, 6)), 50.0);
+        assert_eq!(scale.y(DollarAmount::from(1_000)), MARGIN);
+        assert_eq!(scale.y(DollarAmount::from(10)), MARGIN + 100.0);
+        assert_eq!(scale.date(25.0), date(2020, 1, 4));
+
+        // 100 is halfway between 10 and 1,000 in log scale
+        let chart = LineChart {
+            log_scale: true,
+            ..chart
+        };
+        let scale = chart.scale(bounds).unwrap();
+        assert!((scale.y(DollarAmount::from(100)) - (MARGIN + 50.0)).abs() < 0.01);
+
+        assert_eq!(
+            chart.closest(date(2020, 1, 2)),
+            Some((date(2020, 1, 3), DollarAmount::from(1_000)))
+        );
+        assert_eq!(
+            chart.closest(date(2020, 1, 6)),
+            Some((date(2020, 1, 3), DollarAmount::from(1_000)))
+        );
+        assert_eq!(
+            chart.closest(date(2021, 1, 1)),
+            Some((date(2020, 1, 11), DollarAmount::from(100)))
+        );
+    }
+}
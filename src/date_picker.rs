@@ -0,0 +1,415 @@
+//! Date picker for `iced`: a field to type the date in, and a calendar that
+//! opens under it. Only dates within a range, and for which there is data, can
+//! be picked: the others are greyed out.
+//!
+//! It works from the keyboard too: Enter opens the calendar or confirms the
+//! date, the arrow keys move by a day or a week, Page Up and Page Down by a
+//! month, and Escape closes it.
+use std::ops::RangeInclusive;
+
+use chrono::{Datelike, Days, Months, NaiveDate};
+use iced::{
+    alignment,
+    keyboard::{key::Named, Key, Modifiers},
+    theme,
+    widget::{button, component, text, text_input, Column, Component, Row, Space},
+    Alignment, Element, Length, Size,
+};
+
+use crate::{i18n::Language, key_bindings::key_bindings};
+
+const DAY_WIDTH: f32 = 36.0;
+const INPUT_WIDTH: f32 = 110.0;
+
+pub struct DatePicker<'a, Message> {
+    label: String,
+    value: Option<NaiveDate>,
+    range: RangeInclusive<NaiveDate>,
+    available: Box<dyn Fn(NaiveDate) -> bool + 'a>,
+    on_select: Box<dyn Fn(NaiveDate) -> Message + 'a>,
+    placeholder: String,
+    language: Language,
+}
+
+/// `label` is shown before the field, e.g. "Buy date".
+pub fn date_picker<'a, Message>(
+    label: String,
+    value: Option<NaiveDate>,
+    range: RangeInclusive<NaiveDate>,
+    available: impl Fn(NaiveDate) -> bool + 'a,
+    on_select: impl Fn(NaiveDate) -> Message + 'a,
+) -> DatePicker<'a, Message> {
+    DatePicker {
+        label,
+        value,
+        range,
+        available: Box::new(available),
+        on_select: Box::new(on_select),
+        placeholder: String::new(),
+        language: Language::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    Toggled,
+    /// Day highlighted for the keyboard, and whose month is shown
+    CursorMoved(NaiveDate),
+    Typed(String),
+    /// Enter was pressed in the field
+    Submitted,
+    Closed,
+    Selected(NaiveDate),
+}
+
+#[derive(Debug, Default)]
+pub struct State {
+    /// Day highlighted in the calendar, while it's open
+    cursor: Option<NaiveDate>,
+    /// What's typed in the field, until it's confirmed
+    typed: Option<String>,
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+/// Days of the month by week, starting on Monday, with `None` for days of the
+/// months before and after.
+fn weeks(month: NaiveDate) -> Vec<[Option<NaiveDate>; 7]> {
+    let first = first_of_month(month);
+    let offset = first.weekday().num_days_from_monday() as usize;
+    let days: Vec<NaiveDate> = first
+        .iter_days()
+        .take_while(|date| date.month() == first.month())
+        .collect();
+
+    let mut weeks = vec![];
+    let mut week = [None; 7];
+    for (i, day) in days.into_iter().enumerate() {
+        let weekday = (i + offset) % 7;
+        week[weekday] = Some(day);
+        if weekday == 6 {
+            weeks.push(std::mem::take(&mut week));
+        }
+    }
+    if week.iter().any(Option::is_some) {
+        weeks.push(week);
+    }
+    weeks
+}
+
+/// Where the arrow and page keys move the highlighted day: by a day, a week
+/// or a month
+fn move_cursor(cursor: NaiveDate, key: Named) -> Option<NaiveDate> {
+    match key {
+        Named::ArrowLeft => cursor.checked_sub_days(Days::new(1)),
+        Named::ArrowRight => cursor.checked_add_days(Days::new(1)),
+        Named::ArrowUp => cursor.checked_sub_days(Days::new(7)),
+        Named::ArrowDown => cursor.checked_add_days(Days::new(7)),
+        Named::PageUp => cursor.checked_sub_months(Months::new(1)),
+        Named::PageDown => cursor.checked_add_months(Months::new(1)),
+        _ => None,
+    }
+}
+
+impl<'a, Message> DatePicker<'a, Message> {
+    /// Shown in the field while there's no date, e.g. the expected format
+    pub fn placeholder(mut self, placeholder: String) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    /// Of the month and days of the week, and of the dates in the field
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    fn is_available(&self, date: NaiveDate) -> bool {
+        self.range.contains(&date) && (self.available)(date)
+    }
+
+    fn clamp(&self, date: NaiveDate) -> NaiveDate {
+        date.clamp(*self.range.start(), *self.range.end())
+    }
+
+    /// Day highlighted when opening: the selected one if it can be picked, or
+    /// the closest end of the range.
+    fn initial_cursor(&self) -> NaiveDate {
+        self.clamp(self.value.unwrap_or(*self.range.end()))
+    }
+
+    fn select(&self, state: &mut State, date: NaiveDate) -> Option<Message> {
+        *state = State::default();
+        Some((self.on_select)(date))
+    }
+
+    /// Enter picks the date typed if there's one, and otherwise the day
+    /// highlighted in the calendar, opening it first if needed.
+    fn submit(&self, state: &mut State) -> Option<Message> {
+        match state.typed.take().filter(|typed| !typed.trim().is_empty()) {
+            Some(typed) => match self
+                .language
+                .parse_date(&typed)
+                .filter(|date| self.is_available(*date))
+            {
+                Some(date) => self.select(state, date),
+                // Left as typed, to be corrected
+                None => {
+                    state.typed = Some(typed);
+                    None
+                }
+            },
+            None => match state.cursor {
+                Some(cursor) if self.is_available(cursor) => self.select(state, cursor),
+                Some(_) => None,
+                None => {
+                    state.cursor = Some(self.initial_cursor());
+                    None
+                }
+            },
+        }
+    }
+
+    fn calendar(&self, cursor: NaiveDate) -> Column<'_, Event> {
+        let month = first_of_month(cursor);
+        let previous = cursor
+            .checked_sub_months(Months::new(1))
+            .filter(|_| month > *self.range.start());
+        let next = cursor
+            .checked_add_months(Months::new(1))
+            .filter(|next| first_of_month(*next) <= *self.range.end());
+        let centered = |content: String| {
+            text(content)
+                .width(Length::Fill)
+                .horizontal_alignment(alignment::Horizontal::Center)
+        };
+
+        let header = Row::new()
+            .align_items(Alignment::Center)
+            .push(button("<").on_press_maybe(previous.map(Event::CursorMoved)))
+            .push(centered(self.language.month(month)))
+            .push(button(">").on_press_maybe(next.map(Event::CursorMoved)));
+        let weekdays = self
+            .language
+            .weekdays()
+            .into_iter()
+            .fold(Row::new(), |row, weekday| {
+                row.push(centered(weekday.to_string()).width(DAY_WIDTH))
+            });
+
+        weeks(month).into_iter().fold(
+            Column::new()
+                .width(7.0 * DAY_WIDTH)
+                .spacing(2)
+                .push(header)
+                .push(weekdays),
+            |column, week| {
+                column.push(week.into_iter().fold(Row::new(), |row, day| {
+                    match day {
+                        Some(date) => row.push(
+                            button(centered(date.day().to_string()))
+                                .width(DAY_WIDTH)
+                                .padding(4)
+                                .style(if self.value == Some(date) {
+                                    theme::Button::Primary
+                                } else if date == cursor {
+                                    theme::Button::Secondary
+                                } else {
+                                    theme::Button::Text
+                                })
+                                .on_press_maybe(
+                                    self.is_available(date).then_some(Event::Selected(date)),
+                                ),
+                        ),
+                        None => row.push(Space::with_width(DAY_WIDTH)),
+                    }
+                }))
+            },
+        )
+    }
+}
+
+impl<'a, Message> Component<Message> for DatePicker<'a, Message> {
+    type Event = Event;
+    type State = State;
+
+    fn update(&mut self, state: &mut State, event: Event) -> Option<Message> {
+        match event {
+            Event::Toggled => {
+                state.cursor = match state.cursor {
+                    Some(_) => None,
+                    None => Some(self.initial_cursor()),
+                };
+                None
+            }
+            Event::CursorMoved(date) => {
+                state.cursor = Some(self.clamp(date));
+                None
+            }
+            Event::Typed(typed) => {
+                // The open calendar follows what's typed
+                if let (Some(_), Some(date)) = (state.cursor, self.language.parse_date(&typed)) {
+                    state.cursor = Some(self.clamp(date));
+                }
+                state.typed = Some(typed);
+                None
+            }
+            Event::Submitted => self.submit(state),
+            Event::Closed => {
+                *state = State::default();
+                None
+            }
+            Event::Selected(date) => self.select(state, date),
+        }
+    }
+
+    fn view(&self, state: &State) -> Element<'_, Event> {
+        let value = match &state.typed {
+            Some(typed) => typed.clone(),
+            None => self
+                .value
+                .map(|date| self.language.date(date))
+                .unwrap_or_default(),
+        };
+        let cursor = state.cursor;
+        let editing = state.typed.is_some();
+        let on_key = move |key: &Key, modifiers: Modifiers| {
+            let Key::Named(key) = key else {
+                return None;
+            };
+            match (*key, cursor) {
+                (Named::Enter, _) => Some(Event::Submitted),
+                (Named::Escape, _) if cursor.is_some() || editing => Some(Event::Closed),
+                (Named::ArrowDown, None) if modifiers.alt() => Some(Event::Toggled),
+                (key, Some(cursor)) => move_cursor(cursor, key).map(Event::CursorMoved),
+                _ => None,
+            }
+        };
+
+        let field = Row::new()
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .push(text(&self.label))
+            .push(key_bindings(
+                text_input(&self.placeholder, &value)
+                    .on_input(Event::Typed)
+                    .width(INPUT_WIDTH),
+                on_key,
+            ))
+            .push(button(text("▾")).on_press(Event::Toggled));
+
+        Column::new()
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .push(field)
+            .push_maybe(cursor.map(|cursor| self.calendar(cursor)))
+            .into()
+    }
+
+    fn size_hint(&self) -> Size<Length> {
+        Size {
+            width: Length::Shrink,
+            height: Length::Shrink,
+        }
+    }
+}
+
+impl<'a, Message> From<DatePicker<'a, Message>> for Element<'a, Message>
+where
+    Message: 'a,
+{
+    fn from(date_picker: DatePicker<'a, Message>) -> Self {
+        component(date_picker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price_lookup::test_prices::date;
+
+    #[test]
+    fn test_weeks() {
+        // Starts on a Friday, ends on a Sunday
+        let weeks = weeks(date(2024, 3, 15));
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(weeks[0][..4], [None; 4]);
+        assert_eq!(weeks[0][4], Some(date(2024, 3, 1)));
+        assert_eq!(weeks[4][6], Some(date(2024, 3, 31)));
+
+        // Starts on a Monday
+        let weeks = super::weeks(date(2021, 11, 1));
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(weeks[0][0], Some(date(2021, 11, 1)));
+        assert_eq!(weeks[4][1], Some(date(2021, 11, 30)));
+        assert_eq!(weeks[4][2..], [None; 5]);
+    }
+
+    #[test]
+    fn test_initial_cursor() {
+        let picker = date_picker(
+            String::from("Buy date"),
+            None,
+            date(2010, 7, 18)..=date(2024, 3, 6),
+            |_| true,
+            |date| date,
+        );
+        assert_eq!(picker.initial_cursor(), date(2024, 3, 6));
+
+        let picker = DatePicker {
+            value: Some(date(2009, 1, 3)),
+            ..picker
+        };
+        assert_eq!(picker.initial_cursor(), date(2010, 7, 18));
+        assert!(!picker.is_available(date(2009, 1, 3)));
+        assert!(picker.is_available(date(2015, 1, 1)));
+    }
+
+    #[test]
+    fn test_keyboard() {
+        assert_eq!(
+            move_cursor(date(2024, 2, 29), Named::ArrowDown),
+            Some(date(2024, 3, 7))
+        );
+        assert_eq!(
+            move_cursor(date(2024, 3, 31), Named::PageUp),
+            Some(date(2024, 2, 29))
+        );
+        assert_eq!(move_cursor(date(2024, 3, 31), Named::Tab), None);
+
+        let mut picker = date_picker(
+            String::from("Buy date"),
+            None,
+            date(2010, 7, 18)..=date(2024, 3, 6),
+            |date| date.day() != 1,
+            |date| date,
+        );
+        let mut state = State::default();
+
+        // Enter opens the calendar, then picks the highlighted day
+        assert_eq!(picker.update(&mut state, Event::Submitted), None);
+        assert_eq!(state.cursor, Some(date(2024, 3, 6)));
+        picker.update(&mut state, Event::CursorMoved(date(2024, 4, 6)));
+        assert_eq!(state.cursor, Some(date(2024, 3, 6)));
+        picker.update(&mut state, Event::CursorMoved(date(2024, 3, 1)));
+        assert_eq!(picker.update(&mut state, Event::Submitted), None);
+        picker.update(&mut state, Event::CursorMoved(date(2024, 2, 29)));
+        assert_eq!(
+            picker.update(&mut state, Event::Submitted),
+            Some(date(2024, 2, 29))
+        );
+        assert_eq!(state.cursor, None);
+
+        // A typed date is kept until it can be picked
+        picker.update(&mut state, Event::Typed(String::from("2024-03-01")));
+        assert_eq!(picker.update(&mut state, Event::Submitted), None);
+        assert_eq!(state.typed.as_deref(), Some("2024-03-01"));
+        picker.update(&mut state, Event::Typed(String::from("2024-03-02")));
+        assert_eq!(
+            picker.update(&mut state, Event::Submitted),
+            Some(date(2024, 3, 2))
+        );
+        assert_eq!(state.typed, None);
+    }
+}
@@ -0,0 +1,148 @@
+//! Dates people commonly ask about, to pick them without the calendar. Tops
+//! and bottoms are found in the price history rather than hard-coded, so they
+//! follow the data.
+use chrono::{Days, Months, NaiveDate};
+
+use crate::price_lookup::{PriceHistory, Quote};
+
+/// How far back `years_ago` looks for a price, e.g. over a weekend gap
+const SNAP_DAYS: u64 = 7;
+
+/// Blocks where the mining reward was halved, by date of the block
+const HALVINGS: [(i32, u32, u32); 4] = [(2012, 11, 28), (2016, 7, 9), (2020, 5, 11), (2024, 4, 20)];
+
+/// What happened on a preset's date, named in the chosen language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Occasion {
+    /// The first halving is 1
+    Halving(usize),
+    YearlyHigh(i32),
+    YearlyLow(i32),
+    YearsAgo(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DatePreset {
+    pub occasion: Occasion,
+    pub date: NaiveDate,
+}
+
+/// A whole number of years before today, or the closest day before it that
+/// there's a price for. `None` without a price in the week before.
+pub fn years_ago(prices: &impl PriceHistory, today: NaiveDate, years: u32) -> Option<DatePreset> {
+    let date = today.checked_sub_months(Months::new(12 * years))?;
+    let (date, _) = prices
+        .series(date.checked_sub_days(Days::new(SNAP_DAYS))?, date)
+        .pop()?;
+
+    Some(DatePreset {
+        occasion: Occasion::YearsAgo(years),
+        date,
+    })
+}
+
+/// Highest or lowest closing price of a calendar year, which isn't always
+/// the top or bottom of the cycle, e.g. if it carried on into January
+fn extreme(prices: &impl PriceHistory, year: i32, highest: bool) -> Option<NaiveDate> {
+    let series = prices.series(
+        NaiveDate::from_ymd_opt(year, 1, 1)?,
+        NaiveDate::from_ymd_opt(year, 12, 31)?,
+    );
+    let by_price = |(_, quote): &&(NaiveDate, Quote)| quote.price.fiat_per_btc();
+    let (date, _) = match highest {
+        true => series.iter().max_by_key(by_price),
+        false => series.iter().min_by_key(by_price),
+    }?;
+    Some(*date)
+}
+
+/// Halvings, and the tops and bottom of the last cycles that there are prices
+/// for, by date.
+pub fn notable(prices: &impl PriceHistory) -> Vec<DatePreset> {
+    let halvings = HALVINGS
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, (y, m, d))| {
+            Some(DatePreset {
+                occasion: Occasion::Halving(i + 1),
+                date: NaiveDate::from_ymd_opt(y, m, d)?,
+            })
+        });
+    let extremes = [(2017, true), (2021, true), (2022, false)]
+        .into_iter()
+        .filter_map(|(year, highest)| {
+            Some(DatePreset {
+                occasion: match highest {
+                    true => Occasion::YearlyHigh(year),
+                    false => Occasion::YearlyLow(year),
+                },
+                date: extreme(prices, year, highest)?,
+            })
+        });
+
+    let mut presets: Vec<DatePreset> = halvings.chain(extremes).collect();
+    presets.sort_by_key(|preset| preset.date);
+    presets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price_lookup::test_prices::{date, history};
+
+    #[test]
+    fn test_presets() {
+        let prices = history(&[
+            (date(2017, 6, 1), 2_000),
+            (date(2017, 12, 17), 19_000),
+            (date(2018, 1, 1), 13_000),
+            (date(2021, 4, 14), 63_000),
+            (date(2021, 11, 10), 67_000),
+            (date(2022, 6, 18), 19_000),
+            (date(2022, 11, 21), 15_800),
+        ]);
+        let presets: Vec<(Occasion, NaiveDate)> = notable(&prices)
+            .iter()
+            .map(|preset| (preset.occasion, preset.date))
+            .collect();
+        assert_eq!(
+            presets,
+            [
+                (Occasion::Halving(1), date(2012, 11, 28)),
+                (Occasion::Halving(2), date(2016, 7, 9)),
+                (Occasion::YearlyHigh(2017), date(2017, 12, 17)),
+                (Occasion::Halving(3), date(2020, 5, 11)),
+                (Occasion::YearlyHigh(2021), date(2021, 11, 10)),
+                (Occasion::YearlyLow(2022), date(2022, 11, 21)),
+                (Occasion::Halving(4), date(2024, 4, 20)),
+            ]
+        );
+
+        // Without prices for a year, there's no top to find
+        assert_eq!(notable(&history(&[])).len(), HALVINGS.len());
+
+        let prices = history(&[
+            (date(2019, 3, 6), 3_800),
+            (date(2023, 2, 24), 23_000),
+            (date(2023, 2, 28), 23_100),
+        ]);
+        assert_eq!(
+            years_ago(&prices, date(2024, 2, 29), 1),
+            Some(DatePreset {
+                occasion: Occasion::YearsAgo(1),
+                date: date(2023, 2, 28),
+            })
+        );
+        assert_eq!(
+            years_ago(&prices, date(2024, 3, 6), 5).unwrap().date,
+            date(2019, 3, 6)
+        );
+        // Snaps back to the closest price
+        assert_eq!(
+            years_ago(&prices, date(2024, 2, 27), 1).unwrap().date,
+            date(2023, 2, 24)
+        );
+        // Too far from any price
+        assert_eq!(years_ago(&prices, date(2025, 3, 6), 1), None);
+    }
+}
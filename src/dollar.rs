@@ -1,53 +1,261 @@
 //! Human readable dollar amounts
-use std::fmt::Display;
+//!
+//! Serialized as a decimal string with its currency code, so amounts don't go
+//! through a float and can't be mistaken for another currency:
+//! `{"amount": "1234.56", "currency": "USD"}`
+use std::{fmt::Display, str::FromStr};
 
-#[derive(Copy, Clone)]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const CENTS_IN_DOLLAR: u64 = 100;
+pub const CURRENCY_CODE: &str = "USD";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub struct DollarAmount {
-    dollars: u64,
+    cents: u64,
+}
+
+/// How amounts are written in a language: $1,234.56 in English, or
+/// 1 234,56 $ in French.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AmountFormat {
+    pub thousands_separator: &'static str,
+    pub decimal_separator: &'static str,
+    /// Currency symbol before the number
+    pub prefix: &'static str,
+    /// Currency symbol after the number
+    pub suffix: &'static str,
+}
+
+impl AmountFormat {
+    pub const ENGLISH: AmountFormat = AmountFormat {
+        thousands_separator: ",",
+        decimal_separator: ".",
+        prefix: "$",
+        suffix: "",
+    };
+}
+
+/// Difference between two `DollarAmount`s, e.g. a gain or a loss.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct SignedDollarAmount {
+    cents: i64,
 }
 
 impl DollarAmount {
+    pub const fn from_cents(cents: u64) -> Self {
+        DollarAmount { cents }
+    }
+
+    /// Whole dollars, without the cents
     pub fn dollars(self) -> u64 {
-        self.dollars
+        self.cents / CENTS_IN_DOLLAR
+    }
+
+    pub fn cents(self) -> u64 {
+        self.cents
+    }
+
+    pub fn checked_add(self, rhs: DollarAmount) -> Option<DollarAmount> {
+        self.cents
+            .checked_add(rhs.cents)
+            .map(DollarAmount::from_cents)
+    }
+
+    pub fn checked_mul(self, rhs: u64) -> Option<DollarAmount> {
+        self.cents.checked_mul(rhs).map(DollarAmount::from_cents)
+    }
+
+    pub fn checked_sub(self, rhs: DollarAmount) -> Option<DollarAmount> {
+        self.cents
+            .checked_sub(rhs.cents)
+            .map(DollarAmount::from_cents)
+    }
+
+    /// `self - rhs`, which can be negative.
+    pub fn signed_sub(self, rhs: DollarAmount) -> Option<SignedDollarAmount> {
+        let lhs = i64::try_from(self.cents).ok()?;
+        let rhs = i64::try_from(rhs.cents).ok()?;
+        lhs.checked_sub(rhs).map(SignedDollarAmount::from_cents)
+    }
+
+    /// How many times `rhs` fits in `self`, e.g. 2.0 when an amount doubled.
+    pub fn ratio(self, rhs: DollarAmount) -> Option<f64> {
+        (rhs.cents != 0).then(|| self.cents as f64 / rhs.cents as f64)
+    }
+
+    /// Cents are only shown when there are some: $1,234 or $1,234.56
+    pub fn format(self, format: AmountFormat) -> String {
+        let dollars = separate_thousands(self.dollars())
+            .iter()
+            .map(|v| format!("{v:03}"))
+            .collect::<Vec<String>>()
+            .join(format.thousands_separator);
+        let dollars = match dollars.trim_start_matches('0') {
+            "" => "0",
+            dollars => dollars,
+        };
+        let cents = match self.cents % CENTS_IN_DOLLAR {
+            0 => String::new(),
+            cents => format!("{}{cents:02}", format.decimal_separator),
+        };
+
+        format!("{}{dollars}{cents}{}", format.prefix, format.suffix)
     }
 }
 
-impl From<i32> for DollarAmount {
-    fn from(dollars: i32) -> Self {
-        DollarAmount {
-            dollars: dollars as u64,
-        }
+impl SignedDollarAmount {
+    pub fn from_cents(cents: i64) -> Self {
+        SignedDollarAmount { cents }
+    }
+
+    pub fn cents(self) -> i64 {
+        self.cents
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.cents < 0
+    }
+
+    pub fn abs(self) -> DollarAmount {
+        DollarAmount::from_cents(self.cents.unsigned_abs())
+    }
+
+    /// Same as `DollarAmount`, with a sign: +$1,234.56, -$10
+    pub fn format(self, format: AmountFormat) -> String {
+        let sign = match self.cents.signum() {
+            -1 => "-",
+            1 => "+",
+            _ => "",
+        };
+
+        format!("{sign}{}", self.abs().format(format))
+    }
+}
+
+impl Display for SignedDollarAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.format(AmountFormat::ENGLISH))
     }
 }
 
+/// Any number of dollars that fits in a `u32` fits in cents
 impl From<u32> for DollarAmount {
     fn from(dollars: u32) -> Self {
-        DollarAmount {
-            dollars: dollars as u64,
-        }
+        DollarAmount::from_cents(u64::from(dollars) * CENTS_IN_DOLLAR)
+    }
+}
+
+/// Too many dollars to be counted in cents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "amount too large")
     }
 }
 
-impl From<u64> for DollarAmount {
-    fn from(dollars: u64) -> Self {
-        DollarAmount { dollars }
+impl TryFrom<u64> for DollarAmount {
+    type Error = Overflow;
+
+    fn try_from(dollars: u64) -> Result<Self, Self::Error> {
+        dollars
+            .checked_mul(CENTS_IN_DOLLAR)
+            .map(DollarAmount::from_cents)
+            .ok_or(Overflow)
     }
 }
 
+/// Rounded to the nearest cent
 impl From<f64> for DollarAmount {
     fn from(dollars: f64) -> Self {
         DollarAmount {
-            dollars: dollars as u64,
+            cents: (dollars * CENTS_IN_DOLLAR as f64).round() as u64,
         }
     }
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidNumber,
+    TooManyDecimals,
+    Overflow,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::InvalidNumber => write!(f, "not a positive decimal number"),
+            ParseError::TooManyDecimals => write!(f, "more than 2 decimals"),
+            ParseError::Overflow => write!(f, "amount too large"),
+        }
+    }
+}
+
+/// Parses a plain decimal number of dollars: 1234, 1234.5 or 1234.56
+impl FromStr for DollarAmount {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (dollars, cents) = s.split_once('.').unwrap_or((s, "0"));
+        let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+        if !is_number(dollars) || !is_number(cents) {
+            return Err(ParseError::InvalidNumber);
+        }
+        if cents.len() > 2 {
+            return Err(ParseError::TooManyDecimals);
+        }
+
+        let dollars: u64 = dollars.parse().map_err(|_| ParseError::Overflow)?;
+        let cents: u64 = format!("{cents:0<2}")
+            .parse()
+            .map_err(|_| ParseError::InvalidNumber)?;
+
+        dollars
+            .checked_mul(CENTS_IN_DOLLAR)
+            .and_then(|dollars| dollars.checked_add(cents))
+            .map(DollarAmount::from_cents)
+            .ok_or(ParseError::Overflow)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct WireAmount {
+    amount: String,
+    currency: String,
+}
+
+impl Serialize for DollarAmount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WireAmount {
+            amount: format!("{}.{:02}", self.dollars(), self.cents % CENTS_IN_DOLLAR),
+            currency: CURRENCY_CODE.to_string(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DollarAmount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let wire = WireAmount::deserialize(deserializer)?;
+        if wire.currency != CURRENCY_CODE {
+            return Err(serde::de::Error::custom(format!(
+                "unsupported currency {}, expected {CURRENCY_CODE}",
+                wire.currency
+            )));
+        }
+
+        wire.amount.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 fn separate_thousands(amount: u64) -> Vec<u64> {
     let mut remainder = amount;
     let mut acc = vec![];
     while remainder > 0 {
         acc.push(remainder % 1000);
-        remainder = remainder / 1000;
+        remainder /= 1000;
     }
 
     acc.reverse();
@@ -56,14 +264,7 @@ fn separate_thousands(amount: u64) -> Vec<u64> {
 
 impl Display for DollarAmount {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let dollars = separate_thousands(self.dollars)
-            .iter()
-            .map(|v| format!("{v:03}"))
-            .collect::<Vec<String>>()
-            .join(",");
-        let dollars = dollars.trim_start_matches("0");
-
-        write!(f, "${dollars}")
+        f.write_str(&self.format(AmountFormat::ENGLISH))
     }
 }
 
@@ -87,7 +288,9 @@ mod tests {
     #[test]
     fn test_display() {
         assert_eq!(
-            DollarAmount::from(123_456_789_000u64).to_string(),
+            DollarAmount::try_from(123_456_789_000u64)
+                .unwrap()
+                .to_string(),
             "$123,456,789,000"
         );
         assert_eq!(
@@ -105,5 +308,82 @@ mod tests {
         assert_eq!(DollarAmount::from(100).to_string(), "$100");
         assert_eq!(DollarAmount::from(10).to_string(), "$10");
         assert_eq!(DollarAmount::from(1).to_string(), "$1");
+        assert_eq!(DollarAmount::try_from(u64::MAX), Err(Overflow));
+        assert_eq!(
+            DollarAmount::try_from(u64::MAX / 100),
+            Ok(DollarAmount::from_cents(u64::MAX / 100 * 100))
+        );
+    }
+
+    #[test]
+    fn test_display_cents() {
+        assert_eq!(DollarAmount::from(0).to_string(), "$0");
+        assert_eq!(DollarAmount::from(0.1).to_string(), "$0.10");
+        assert_eq!(DollarAmount::from_cents(5).to_string(), "$0.05");
+        assert_eq!(DollarAmount::from(66_999.7).to_string(), "$66,999.70");
+        assert_eq!(DollarAmount::from(1.005).dollars(), 1);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("1234".parse(), Ok(DollarAmount::from(1_234)));
+        assert_eq!("1234.5".parse(), Ok(DollarAmount::from_cents(123_450)));
+        assert_eq!("0.05".parse(), Ok(DollarAmount::from_cents(5)));
+        assert_eq!(
+            "1.234".parse::<DollarAmount>(),
+            Err(ParseError::TooManyDecimals)
+        );
+        assert_eq!("-1".parse::<DollarAmount>(), Err(ParseError::InvalidNumber));
+        assert_eq!("1.".parse::<DollarAmount>(), Err(ParseError::InvalidNumber));
+        assert_eq!(
+            "1,000".parse::<DollarAmount>(),
+            Err(ParseError::InvalidNumber)
+        );
+        assert_eq!(
+            "184467440737095517".parse::<DollarAmount>(),
+            Err(ParseError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_wire_format() {
+        let amount = DollarAmount::from_cents(123_456);
+        let json = r#"{"amount":"1234.56","currency":"USD"}"#;
+        assert_eq!(serde_json::to_string(&amount).unwrap(), json);
+        assert_eq!(serde_json::from_str::<DollarAmount>(json).unwrap(), amount);
+
+        assert_eq!(
+            serde_json::to_string(&DollarAmount::from(10)).unwrap(),
+            r#"{"amount":"10.00","currency":"USD"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<DollarAmount>(r#"{"amount":"10","currency":"USD"}"#).unwrap(),
+            DollarAmount::from(10)
+        );
+        assert!(
+            serde_json::from_str::<DollarAmount>(r#"{"amount":"10","currency":"EUR"}"#).is_err()
+        );
+        assert!(serde_json::from_str::<DollarAmount>(r#"{"amount":10,"currency":"USD"}"#).is_err());
+    }
+
+    #[test]
+    fn test_signed_amounts() {
+        let gain = DollarAmount::from(1_500)
+            .signed_sub(DollarAmount::from(1_000))
+            .unwrap();
+        assert_eq!(gain.to_string(), "+$500");
+
+        let loss = DollarAmount::from(1_000)
+            .signed_sub(DollarAmount::from_cents(150_050))
+            .unwrap();
+        assert!(loss.is_negative());
+        assert_eq!(loss.to_string(), "-$500.50");
+
+        assert_eq!(SignedDollarAmount::default().to_string(), "$0");
+        assert_eq!(
+            DollarAmount::from(3_000).ratio(DollarAmount::from(1_000)),
+            Some(3.0)
+        );
+        assert_eq!(DollarAmount::from(3_000).ratio(DollarAmount::from(0)), None);
     }
 }
@@ -0,0 +1,255 @@
+//! Costs of converting between fiat and bitcoin: trading fees, the spread
+//! around the mid price, and on-chain withdrawal fees.
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bitcoin::{BitcoinAmount, MilliSatAmount},
+    dollar::DollarAmount,
+    price::{mul_div, Price, Rounding},
+};
+
+pub(crate) const BASIS_POINTS: u64 = 10_000;
+
+/// Fees charged on every buy and sell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct FeeModel {
+    /// Trading fee, in basis points of the traded amount
+    pub percent_bps: u64,
+    /// Trading fee charged on every trade, whatever the amount
+    pub flat: DollarAmount,
+    /// Distance between the mid price and the price you actually get, in
+    /// basis points: buying costs more than the mid, selling gets less
+    pub spread_bps: u64,
+    /// Paid after every buy, to withdraw to your own wallet
+    pub withdrawal: BitcoinAmount,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Overflow,
+}
+
+fn bps_of(amount: DollarAmount, bps: u64) -> Result<DollarAmount, Error> {
+    mul_div(amount.cents(), bps, BASIS_POINTS, Rounding::Up)
+        .map(DollarAmount::from_cents)
+        .ok_or(Error::Overflow)
+}
+
+/// Price moved away from the mid by `spread_bps`, in the exchange's favour.
+fn with_spread(price: Price, spread_bps: u64, buying: bool) -> Result<Price, Error> {
+    let (factor, rounding) = match buying {
+        true => (BASIS_POINTS + spread_bps, Rounding::Up),
+        false => (BASIS_POINTS.saturating_sub(spread_bps), Rounding::Down),
+    };
+
+    mul_div(price.fiat_per_btc().cents(), factor, BASIS_POINTS, rounding)
+        .map(|cents| Price::per_btc(DollarAmount::from_cents(cents)))
+        .ok_or(Error::Overflow)
+}
+
+impl FeeModel {
+    pub fn is_free(&self) -> bool {
+        *self == FeeModel::default()
+    }
+
+    /// Bitcoin received for `fiat` at the `mid` price, once fees are paid.
+    /// Nothing is charged when nothing is bought, and fees higher than the
+    /// amount leave nothing rather than failing, so that one small purchase
+    /// doesn't sink a whole dollar-cost averaging schedule.
+    pub fn buy(&self, fiat: DollarAmount, mid: Price) -> Result<MilliSatAmount, Error> {
+        if fiat == DollarAmount::default() {
+            return Ok(MilliSatAmount::default());
+        }
+
+        let fees = bps_of(fiat, self.percent_bps)?
+            .checked_add(self.flat)
+            .ok_or(Error::Overflow)?;
+        let spent = fiat.checked_sub(fees).unwrap_or_default();
+        let bought = with_spread(mid, self.spread_bps, true)?
+            .to_msats(spent, Rounding::Down)
+            .ok_or(Error::Overflow)?;
+        let withdrawal = self.withdrawal.to_msats().ok_or(Error::Overflow)?;
+
+        Ok(bought.checked_sub(withdrawal).unwrap_or_default())
+    }
+
+    /// Fiat received for selling `btc` at the `mid` price, once fees are paid.
+    /// Like buying, it's free to sell nothing, and fees can't take more than
+    /// the proceeds.
+    pub fn sell(&self, btc: BitcoinAmount, mid: Price) -> Result<DollarAmount, Error> {
+        if btc == BitcoinAmount::default() {
+            return Ok(DollarAmount::default());
+        }
+
+        let proceeds = with_spread(mid, self.spread_bps, false)?
+            .to_fiat_rounded(btc, Rounding::Down)
+            .ok_or(Error::Overflow)?;
+        let fees = bps_of(proceeds, self.percent_bps)?
+            .checked_add(self.flat)
+            .ok_or(Error::Overflow)?;
+
+        Ok(proceeds.checked_sub(fees).unwrap_or_default())
+    }
+}
+
+/// Rough fees for common ways of buying bitcoin. Actual fees vary between
+/// providers and change over time, so these are only illustrations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeePreset {
+    pub name: FeePresetName,
+    pub fees: FeeModel,
+}
+
+/// What a preset's fees are typical of, named in the chosen language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeePresetName {
+    NoFees,
+    LowCostExchange,
+    BrokerageApp,
+    BitcoinAtm,
+    Custom,
+}
+
+impl FeePreset {
+    pub const ALL: [FeePreset; 4] = [
+        FeePreset::NONE,
+        FeePreset {
+            name: FeePresetName::LowCostExchange,
+            fees: FeeModel {
+                percent_bps: 25,
+                flat: DollarAmount::from_cents(0),
+                spread_bps: 5,
+                withdrawal: BitcoinAmount::from(10_000),
+            },
+        },
+        FeePreset {
+            name: FeePresetName::BrokerageApp,
+            fees: FeeModel {
+                percent_bps: 150,
+                flat: DollarAmount::from_cents(99),
+                spread_bps: 50,
+                withdrawal: BitcoinAmount::from(0),
+            },
+        },
+        FeePreset {
+            name: FeePresetName::BitcoinAtm,
+            fees: FeeModel {
+                percent_bps: 1_000,
+                flat: DollarAmount::from_cents(0),
+                spread_bps: 100,
+                withdrawal: BitcoinAmount::from(0),
+            },
+        },
+    ];
+    pub const NONE: FeePreset = FeePreset {
+        name: FeePresetName::NoFees,
+        fees: FeeModel {
+            percent_bps: 0,
+            flat: DollarAmount::from_cents(0),
+            spread_bps: 0,
+            withdrawal: BitcoinAmount::from(0),
+        },
+    };
+
+    /// Fees set one by one, e.g. starting from those of another preset
+    pub fn custom(fees: FeeModel) -> FeePreset {
+        FeePreset {
+            name: FeePresetName::Custom,
+            fees,
+        }
+    }
+
+    pub fn is_custom(&self) -> bool {
+        self.name == FeePresetName::Custom
+    }
+
+    /// The preset with these fees, if there's one
+    pub fn from_fees(fees: FeeModel) -> FeePreset {
+        FeePreset::ALL
+            .into_iter()
+            .find(|preset| preset.fees == fees)
+            .unwrap_or(FeePreset::custom(fees))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_fees() {
+        let fees = FeeModel::default();
+        let price = Price::per_btc(DollarAmount::from(50_000));
+
+        assert!(fees.is_free());
+        assert_eq!(
+            fees.buy(DollarAmount::from(1_000), price).unwrap(),
+            MilliSatAmount::from_msats(2_000_000_000)
+        );
+        assert_eq!(
+            fees.sell(BitcoinAmount::from(2_000_000), price).unwrap(),
+            DollarAmount::from(1_000)
+        );
+    }
+
+    #[test]
+    fn test_fees() {
+        let fees = FeeModel {
+            percent_bps: 100,
+            flat: DollarAmount::from(10),
+            spread_bps: 100,
+            withdrawal: BitcoinAmount::from(5_000),
+        };
+        let price = Price::per_btc(DollarAmount::from(50_000));
+
+        // $1,000 - $10 (1%) - $10 = $980 at $50,500 = 1 940 594.059 sats,
+        // minus 5 000 sats to withdraw
+        assert_eq!(
+            fees.buy(DollarAmount::from(1_000), price).unwrap(),
+            MilliSatAmount::from_msats(1_935_594_059)
+        );
+
+        // 2M sats at $49,500 = $990, - $9.90 (1%) - $10
+        assert_eq!(
+            fees.sell(BitcoinAmount::from(2_000_000), price).unwrap(),
+            DollarAmount::from_cents(97_010)
+        );
+
+        // Fees eat small trades whole, and aren't charged on nothing
+        assert_eq!(
+            fees.buy(DollarAmount::from(10), price).unwrap(),
+            MilliSatAmount::default()
+        );
+        assert_eq!(
+            fees.sell(BitcoinAmount::from(1_000), price).unwrap(),
+            DollarAmount::default()
+        );
+        assert_eq!(
+            fees.buy(DollarAmount::default(), price).unwrap(),
+            MilliSatAmount::default()
+        );
+        assert_eq!(
+            fees.sell(BitcoinAmount::default(), price).unwrap(),
+            DollarAmount::default()
+        );
+    }
+
+    #[test]
+    fn test_presets() {
+        assert!(FeePreset::NONE.fees.is_free());
+        assert!(FeePreset::ALL[1..]
+            .iter()
+            .all(|preset| !preset.fees.is_free()));
+
+        let fees = FeeModel {
+            percent_bps: 42,
+            ..Default::default()
+        };
+        assert_eq!(
+            FeePreset::from_fees(FeePreset::ALL[2].fees),
+            FeePreset::ALL[2]
+        );
+        assert_eq!(FeePreset::from_fees(fees), FeePreset::custom(fees));
+        assert!(FeePreset::custom(fees).is_custom());
+    }
+}
@@ -6,7 +6,7 @@ use std::collections::HashMap;
 use chrono::NaiveDate;
 use serde::{Deserialize, Deserializer};
 
-use crate::{bitcoin::BitcoinAmount, dollar::DollarAmount};
+use crate::{dollar::DollarAmount, price::Price};
 
 #[derive(Deserialize)]
 //"Date","Price","Open","High","Low","Vol.","Change %"
@@ -29,15 +29,12 @@ struct CsvRecord {
 
 pub fn get_prices_from_csv(
     reader: impl std::io::Read,
-) -> Result<HashMap<NaiveDate, (DollarAmount, BitcoinAmount)>, csv::Error> {
+) -> Result<HashMap<NaiveDate, Price>, csv::Error> {
     let mut reader = csv::Reader::from_reader(reader);
     let mut records = HashMap::new();
     for record in reader.deserialize() {
         let record: CsvRecord = record?;
-        records.insert(
-            record.date,
-            (DollarAmount::from(record.price), BitcoinAmount::one_btc()),
-        );
+        records.insert(record.date, Price::per_btc(record.price));
     }
 
     Ok(records)
@@ -58,7 +55,7 @@ mod deserialize_date {
     use chrono::NaiveDate;
     use serde::{self, Deserialize, Deserializer};
 
-    const FORMAT: &'static str = "%m/%d/%Y";
+    const FORMAT: &str = "%m/%d/%Y";
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
     where
@@ -0,0 +1,1438 @@
+//! Translations of the calculator's text into English, French and Spanish,
+//! with dates, amounts and percentages written the way each language does.
+//!
+//! Each message is a `Msg`, with whatever it shows as data, so every language
+//! has to translate every message, and can order and pluralise it as it
+//! needs.
+use std::fmt::Display;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    benchmark::Benchmark,
+    bitcoin::BitcoinUnit,
+    date_presets::{DatePreset, Occasion},
+    dollar::{AmountFormat, DollarAmount, SignedDollarAmount},
+    fees::FeePresetName,
+    inflation::Dollars,
+    price::Price,
+    price_lookup::{Source, Unavailable},
+    rebalancing::{Period, RebalanceRule},
+    scenario::{self, AllocationMode, Frequency, Strategy},
+    tax::{CostBasisMethod, TaxRules},
+    theme::ThemeMode,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+    Spanish,
+}
+
+/// Narrow no-break space, between thousands and before punctuation in French
+const NNBSP: &str = "\u{202F}";
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::English, Language::French, Language::Spanish];
+
+    /// From a language tag or locale, e.g. `fr-FR`, `es_ES.UTF-8` or `en`
+    pub fn from_tag(tag: &str) -> Option<Language> {
+        let code = tag.get(..2)?.to_ascii_lowercase();
+        match code.as_str() {
+            "en" => Some(Language::English),
+            "fr" => Some(Language::French),
+            "es" => Some(Language::Spanish),
+            _ => None,
+        }
+    }
+
+    /// Language of the browser on the web, or of the locale on desktop.
+    /// English if it isn't translated.
+    pub fn detect() -> Language {
+        #[cfg(target_arch = "wasm32")]
+        let tags: Vec<String> = web_sys::window()
+            .and_then(|window| window.navigator().language())
+            .into_iter()
+            .collect();
+        #[cfg(not(target_arch = "wasm32"))]
+        let tags: Vec<String> = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .collect();
+
+        tags.iter()
+            .find_map(|tag| Language::from_tag(tag))
+            .unwrap_or_default()
+    }
+
+    pub fn amount_format(self) -> AmountFormat {
+        match self {
+            Language::English => AmountFormat::ENGLISH,
+            Language::French => AmountFormat {
+                thousands_separator: NNBSP,
+                decimal_separator: ",",
+                prefix: "",
+                suffix: "\u{A0}$",
+            },
+            Language::Spanish => AmountFormat {
+                thousands_separator: ".",
+                decimal_separator: ",",
+                prefix: "",
+                suffix: "\u{A0}US$",
+            },
+        }
+    }
+
+    pub fn amount(self, amount: DollarAmount) -> String {
+        amount.format(self.amount_format())
+    }
+
+    pub fn signed_amount(self, amount: SignedDollarAmount) -> String {
+        amount.format(self.amount_format())
+    }
+
+    pub fn price(self, price: Price) -> String {
+        format!("{}/BTC", self.amount(price.fiat_per_btc()))
+    }
+
+    pub fn date(self, date: NaiveDate) -> String {
+        match self {
+            Language::English => date.format("%Y-%m-%d").to_string(),
+            Language::French | Language::Spanish => date.format("%d/%m/%Y").to_string(),
+        }
+    }
+
+    /// Reads a date typed the way [`Language::date`] writes it. ISO dates are
+    /// understood in every language.
+    pub fn parse_date(self, date: &str) -> Option<NaiveDate> {
+        let date = date.trim();
+        let iso = NaiveDate::parse_from_str(date, "%Y-%m-%d");
+        match self {
+            Language::English => iso.ok(),
+            Language::French | Language::Spanish => iso
+                .or_else(|_| NaiveDate::parse_from_str(date, "%d/%m/%Y"))
+                .ok(),
+        }
+    }
+
+    /// A ratio as a signed percentage, e.g. "+12.5%" for 0.125
+    pub fn percent(self, ratio: f64) -> String {
+        let percent = format!("{:+.1}", ratio * 100.0);
+        match self {
+            Language::English => format!("{percent}%"),
+            Language::French | Language::Spanish => {
+                format!("{}\u{A0}%", percent.replace('.', ","))
+            }
+        }
+    }
+
+    /// e.g. "1.5" in English, "1,5" in French
+    fn decimal(self, number: f64, decimals: usize) -> String {
+        let number = format!("{number:.decimals$}");
+        match self {
+            Language::English => number,
+            Language::French | Language::Spanish => number.replace('.', ","),
+        }
+    }
+
+    /// Basis points as a percentage, without decimals when they're all 0
+    fn rate(self, bps: u64) -> String {
+        match bps % 100 {
+            0 => (bps / 100).to_string(),
+            _ => self.decimal(bps as f64 / 100.0, 2),
+        }
+    }
+
+    /// How many times what was invested, e.g. "x2.50"
+    pub fn multiple(self, multiple: f64) -> String {
+        format!("x{}", self.decimal(multiple, 2))
+    }
+
+    /// Options of a pick list, each written as `msg` says
+    pub fn choices<'a, T: Copy>(
+        self,
+        values: impl IntoIterator<Item = T>,
+        msg: impl Fn(T) -> Msg<'a>,
+    ) -> Vec<Choice<T>> {
+        values
+            .into_iter()
+            .map(|value| self.choice(value, &msg))
+            .collect()
+    }
+
+    pub fn choice<'a, T: Copy>(self, value: T, msg: impl Fn(T) -> Msg<'a>) -> Choice<T> {
+        Choice {
+            value,
+            label: self.tr(msg(value)),
+        }
+    }
+
+    /// Whether `count` takes the singular: 0 does in French, but not in
+    /// English or Spanish
+    fn is_singular(self, count: u64) -> bool {
+        match self {
+            Language::French => count <= 1,
+            Language::English | Language::Spanish => count == 1,
+        }
+    }
+
+    pub fn weekdays(self) -> [&'static str; 7] {
+        match self {
+            Language::English => ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"],
+            Language::French => ["Lu", "Ma", "Me", "Je", "Ve", "Sa", "Di"],
+            Language::Spanish => ["Lu", "Ma", "Mi", "Ju", "Vi", "Sá", "Do"],
+        }
+    }
+
+    /// e.g. "March 2024"
+    pub fn month(self, date: NaiveDate) -> String {
+        let months = match self {
+            Language::English => [
+                "January",
+                "February",
+                "March",
+                "April",
+                "May",
+                "June",
+                "July",
+                "August",
+                "September",
+                "October",
+                "November",
+                "December",
+            ],
+            Language::French => [
+                "janvier",
+                "février",
+                "mars",
+                "avril",
+                "mai",
+                "juin",
+                "juillet",
+                "août",
+                "septembre",
+                "octobre",
+                "novembre",
+                "décembre",
+            ],
+            Language::Spanish => [
+                "enero",
+                "febrero",
+                "marzo",
+                "abril",
+                "mayo",
+                "junio",
+                "julio",
+                "agosto",
+                "septiembre",
+                "octubre",
+                "noviembre",
+                "diciembre",
+            ],
+        };
+        format!("{} {}", months[date.month0() as usize], date.year())
+    }
+
+    fn frequency(self, frequency: Frequency) -> &'static str {
+        match (self, frequency) {
+            (Language::English, Frequency::Daily) => "day",
+            (Language::English, Frequency::Weekly) => "week",
+            (Language::English, Frequency::Monthly) => "month",
+            (Language::French, Frequency::Daily) => "jour",
+            (Language::French, Frequency::Weekly) => "semaine",
+            (Language::French, Frequency::Monthly) => "mois",
+            (Language::Spanish, Frequency::Daily) => "día",
+            (Language::Spanish, Frequency::Weekly) => "semana",
+            (Language::Spanish, Frequency::Monthly) => "mes",
+        }
+    }
+
+    fn source(self, source: Source) -> &'static str {
+        match (self, source) {
+            (_, Source::CoinDesk) => "CoinDesk",
+            (Language::English, Source::History) => "historical data",
+            (Language::French, Source::History) => "données historiques",
+            (Language::Spanish, Source::History) => "datos históricos",
+        }
+    }
+
+    fn error(self, err: scenario::Error) -> String {
+        use scenario::Error;
+        match (self, err) {
+            (Language::English, _) => err.to_string(),
+            (Language::French, Error::MissingPrice(day)) => {
+                format!("aucun prix disponible le {}", self.date(day))
+            }
+            (Language::French, Error::EvaluationBeforeBuy) => {
+                String::from("la date d'évaluation est avant la date d'achat")
+            }
+            (Language::French, Error::NothingInvested) => String::from("rien n'a été investi"),
+            (Language::French, Error::InvalidAllocation) => {
+                String::from("impossible de mettre plus que le montant en bitcoin")
+            }
+            (Language::French, Error::Overflow) => String::from("les montants sont trop élevés"),
+            (Language::Spanish, Error::MissingPrice(day)) => {
+                format!("no hay precio disponible el {}", self.date(day))
+            }
+            (Language::Spanish, Error::EvaluationBeforeBuy) => {
+                String::from("la fecha de evaluación es anterior a la fecha de compra")
+            }
+            (Language::Spanish, Error::NothingInvested) => String::from("no se invirtió nada"),
+            (Language::Spanish, Error::InvalidAllocation) => {
+                String::from("no se puede poner más que la cantidad en bitcoin")
+            }
+            (Language::Spanish, Error::Overflow) => {
+                String::from("las cantidades son demasiado grandes")
+            }
+        }
+    }
+
+    /// Names of the bundled tax presets. Others, e.g. from a local file, are
+    /// kept as they're written.
+    fn jurisdiction(self, name: &str) -> &str {
+        match (self, name) {
+            (Language::French, "United States (single filer)") => "États-Unis (déclarant seul)",
+            (Language::French, "United Kingdom (higher rate)") => "Royaume-Uni (taux supérieur)",
+            (Language::French, "France (flat tax)") => "France (prélèvement forfaitaire)",
+            (Language::French, "Germany (top rate)") => "Allemagne (taux maximal)",
+            (Language::Spanish, "United States (single filer)") => {
+                "Estados Unidos (declarante individual)"
+            }
+            (Language::Spanish, "United Kingdom (higher rate)") => "Reino Unido (tipo superior)",
+            (Language::Spanish, "France (flat tax)") => "Francia (tipo único)",
+            (Language::Spanish, "Germany (top rate)") => "Alemania (tipo máximo)",
+            _ => name,
+        }
+    }
+
+    fn unavailable(self, reason: Unavailable) -> String {
+        match (self, reason) {
+            (Language::English, _) => reason.to_string(),
+            (Language::French, Unavailable::Future) => String::from("c'est dans le futur"),
+            (Language::French, Unavailable::BeforeHistory(first)) => {
+                format!("l'historique des prix commence le {}", self.date(first))
+            }
+            (Language::French, Unavailable::Missing) => String::from(
+                "il manque à l'historique des prix, et seul le prix du jour peut être récupéré",
+            ),
+            (Language::French, Unavailable::NotFetchedOnWeb) => {
+                String::from("les prix en direct ne sont pas encore récupérés sur le web")
+            }
+            (Language::Spanish, Unavailable::Future) => String::from("está en el futuro"),
+            (Language::Spanish, Unavailable::BeforeHistory(first)) => {
+                format!("el historial de precios empieza el {}", self.date(first))
+            }
+            (Language::Spanish, Unavailable::Missing) => String::from(
+                "falta en el historial de precios, y solo se puede obtener el precio de hoy",
+            ),
+            (Language::Spanish, Unavailable::NotFetchedOnWeb) => {
+                String::from("los precios en directo aún no se obtienen en la web")
+            }
+        }
+    }
+}
+
+impl Display for Language {
+    /// In the language itself, so it can be found by those who speak it
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Language::English => write!(f, "English"),
+            Language::French => write!(f, "Français"),
+            Language::Spanish => write!(f, "Español"),
+        }
+    }
+}
+
+/// An option of a pick list, shown in the chosen language
+#[derive(Debug, Clone, PartialEq)]
+pub struct Choice<T> {
+    pub value: T,
+    label: String,
+}
+
+impl<T> Display for Choice<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
+/// Everything the calculator says
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Msg<'a> {
+    /// Of the window, with the headline of the result if there's one
+    Title(Option<&'a str>),
+    BuyDate,
+    SellDate,
+    /// How to type a date, e.g. "YYYY-MM-DD"
+    DateFormat,
+    Today,
+    Occasion(Occasion),
+    /// The occasion, then its date
+    DatePreset(DatePreset),
+    NotableDates,
+    TypeANumber,
+    TypeAnAmount,
+    AmountTooLarge,
+    PickBuyDate,
+    Loading(NaiveDate),
+    NoPrice(NaiveDate, Unavailable),
+    FetchFailed(NaiveDate, &'a str),
+    Retry,
+    NoResult(scenario::Error),
+    ConvertedEverything {
+        invested: DollarAmount,
+    },
+    ConvertedPart {
+        bitcoin: DollarAmount,
+        invested: DollarAmount,
+    },
+    BoughtOn {
+        date: NaiveDate,
+        price: Price,
+        source: Source,
+    },
+    BoughtEvery {
+        amount: DollarAmount,
+        frequency: Frequency,
+        since: NaiveDate,
+        invested: DollarAmount,
+    },
+    AverageCost(Price),
+    SkippedPurchases(u64),
+    MoreThan21Million,
+    ValueToday(DollarAmount),
+    ValueOn(NaiveDate, DollarAmount),
+    RealValue {
+        value: DollarAmount,
+        /// `None` for today's dollars
+        base_year: Option<i32>,
+        invested: DollarAmount,
+        real_return: f64,
+        nominal_return: f64,
+    },
+    /// Why there's no `RealValue`
+    NoCpiData {
+        first: NaiveDate,
+        last: NaiveDate,
+    },
+    BitcoinAndCash {
+        bitcoin: DollarAmount,
+        cash: DollarAmount,
+    },
+    /// What isn't converted is kept in an asset, rather than in cash
+    BitcoinAndAsset {
+        bitcoin: DollarAmount,
+        rest: DollarAmount,
+        asset: &'a str,
+    },
+    /// The asset has no price for the scenario's dates
+    RestKeptInCash(&'a str),
+    /// What isn't converted is kept in, `None` for cash
+    RestIn(Option<&'a str>),
+    BeforeFees {
+        gross: DollarAmount,
+        fees: DollarAmount,
+    },
+    SubSatPrecision,
+    /// Label of the amount of sats from which `Auto` shows ₿
+    BtcThreshold,
+    TradingFee,
+    Spread,
+    FlatFee,
+    WithdrawalFee,
+    SavedScenarios,
+    Name,
+    Save,
+    Strategy(Strategy),
+    FeePreset(FeePresetName),
+    AllocationMode(AllocationMode),
+    RebalanceRule(RebalanceRule),
+    CostBasisMethod(CostBasisMethod),
+    /// Names of the bundled presets are translated, those of a local file
+    /// are shown as they're written
+    TaxRules(&'a TaxRules),
+    BitcoinUnit(BitcoinUnit),
+    Dollars(Dollars),
+    ThemeMode(ThemeMode),
+    /// `None` to follow the system or browser
+    Language(Option<Language>),
+    // Chart lines and markers, and table headings
+    Price,
+    Value,
+    Invested,
+    InvestedWithInflation,
+    Gain,
+    Multiple,
+    Buy,
+    Sell,
+    LogScale,
+    /// The hovered point of a chart
+    ChartPoint(NaiveDate, DollarAmount),
+    InvestedAmount(DollarAmount),
+    CostBasis(Price),
+    ValueAmount(DollarAmount),
+    GainAmount(SignedDollarAmount),
+    TotalReturn(f64),
+    YearlyGrowth(f64),
+    /// Yearly, as a ratio
+    Volatility(f64),
+    MaxDrawdown {
+        loss: f64,
+        peak: NaiveDate,
+        trough: NaiveDate,
+    },
+    BestDay(NaiveDate, f64),
+    WorstDay(NaiveDate, f64),
+    /// Followed by the risk-free rate input
+    SharpeRatio(Option<f64>),
+    RiskFreeRate,
+    PercentSold,
+    TaxEstimate {
+        short_term_gain: SignedDollarAmount,
+        long_term_gain: SignedDollarAmount,
+        tax: DollarAmount,
+        after_tax_value: DollarAmount,
+    },
+    SavingsRate,
+    /// The row of the scenario itself, above the benchmarks
+    Bitcoin,
+    Benchmark(Benchmark<'a>),
+    /// Chart lines of the rebalancing simulation
+    Rebalanced,
+    BuyAndHold,
+    RebalancedValues {
+        rebalanced: DollarAmount,
+        rebalances: usize,
+        buy_and_hold: DollarAmount,
+    },
+}
+
+impl Language {
+    pub fn tr(self, msg: Msg) -> String {
+        match self {
+            Language::English => self.english(msg),
+            Language::French => self.french(msg),
+            Language::Spanish => self.spanish(msg),
+        }
+    }
+
+    fn english(self, msg: Msg) -> String {
+        let amount = |amount| self.amount(amount);
+        let date = |date| self.date(date);
+        match msg {
+            Msg::Title(Some(summary)) => format!("What if... {summary}"),
+            Msg::Title(None) => String::from("What if..."),
+            Msg::BuyDate => String::from("Buy date"),
+            Msg::SellDate => String::from("Sell date"),
+            Msg::DateFormat => String::from("YYYY-MM-DD"),
+            Msg::Today => String::from("Today"),
+            Msg::Occasion(occasion) => match occasion {
+                Occasion::Halving(number) => {
+                    match ["First", "Second", "Third", "Fourth"].get(number.wrapping_sub(1)) {
+                        Some(ordinal) => format!("{ordinal} halving"),
+                        None => format!("Halving no. {number}"),
+                    }
+                }
+                Occasion::YearlyHigh(year) => format!("{year} high"),
+                Occasion::YearlyLow(year) => format!("{year} low"),
+                Occasion::YearsAgo(1) => String::from("1 year ago"),
+                Occasion::YearsAgo(years) => format!("{years} years ago"),
+            },
+            Msg::DatePreset(preset) => format!(
+                "{} ({})",
+                self.english(Msg::Occasion(preset.occasion)),
+                date(preset.date)
+            ),
+            Msg::NotableDates => String::from("Notable dates"),
+            Msg::TypeANumber => String::from("Type a number"),
+            Msg::TypeAnAmount => String::from("Type an amount to invest"),
+            Msg::AmountTooLarge => String::from("That amount is too large"),
+            Msg::PickBuyDate => String::from("Pick a buy date"),
+            Msg::Loading(day) => format!("Loading the price on {}…", date(day)),
+            Msg::NoPrice(day, reason) => {
+                format!("No price on {}: {}", date(day), self.unavailable(reason))
+            }
+            Msg::FetchFailed(day, err) => {
+                format!("Couldn't fetch the price on {}: {err}", date(day))
+            }
+            Msg::Retry => String::from("Retry"),
+            Msg::NoResult(err) => format!("No result: {}", self.error(err)),
+            Msg::ConvertedEverything { invested } => format!(
+                "If you converted your entire net worth of {} into",
+                amount(invested)
+            ),
+            Msg::ConvertedPart { bitcoin, invested } => format!(
+                "If you converted {} of your net worth of {} into",
+                amount(bitcoin),
+                amount(invested)
+            ),
+            Msg::BoughtOn {
+                date: day,
+                price,
+                source,
+            } => format!(
+                "on {} at {} ({})",
+                date(day),
+                self.price(price),
+                self.source(source)
+            ),
+            Msg::BoughtEvery {
+                amount: bought,
+                frequency,
+                since,
+                invested,
+            } => format!(
+                "If you had bought {} of bitcoin every {} since {}, you would have converted {} into",
+                amount(bought),
+                self.frequency(frequency),
+                date(since),
+                amount(invested)
+            ),
+            Msg::AverageCost(price) => format!("at an average of {}", self.price(price)),
+            Msg::SkippedPurchases(count) => match self.is_singular(count) {
+                true => format!("{count} purchase was skipped, for lack of price data"),
+                false => format!("{count} purchases were skipped, for lack of price data"),
+            },
+            Msg::MoreThan21Million => {
+                String::from("That's more than the 21 million bitcoin that will ever exist!")
+            }
+            Msg::ValueToday(value) => format!("Your net worth today would be {}", amount(value)),
+            Msg::ValueOn(day, value) => format!(
+                "Your net worth on {} would have been {}",
+                date(day),
+                amount(value)
+            ),
+            Msg::RealValue {
+                value,
+                base_year,
+                invested,
+                real_return,
+                nominal_return,
+            } => format!(
+                "{} {}, for {} invested: a real return of {}, vs {} before inflation",
+                amount(value),
+                match base_year {
+                    Some(year) => format!("in {year} dollars"),
+                    None => String::from("in today's dollars"),
+                },
+                amount(invested),
+                self.percent(real_return),
+                self.percent(nominal_return)
+            ),
+            Msg::NoCpiData { first, last } => format!(
+                "No inflation-adjusted value: CPI data only covers {} to {}",
+                self.month(first),
+                self.month(last)
+            ),
+            Msg::BitcoinAndCash { bitcoin, cash } => format!(
+                "{} in bitcoin and {} in cash",
+                amount(bitcoin),
+                amount(cash)
+            ),
+            Msg::BitcoinAndAsset {
+                bitcoin,
+                rest,
+                asset,
+            } => format!(
+                "{} in bitcoin and {} in {asset}",
+                amount(bitcoin),
+                amount(rest)
+            ),
+            Msg::RestKeptInCash(asset) => {
+                format!("No {asset} prices for these dates, so the rest is counted as cash")
+            }
+            Msg::RestIn(None) => String::from("Rest in cash"),
+            Msg::RestIn(Some(asset)) => format!("Rest in {asset}"),
+            Msg::BeforeFees { gross, fees } => format!(
+                "{} before fees, {} paid in fees",
+                amount(gross),
+                amount(fees)
+            ),
+            Msg::SubSatPrecision => String::from("Sub-sat precision"),
+            Msg::BtcThreshold => String::from("Show ₿ from (sats)"),
+            Msg::TradingFee => String::from("Trading fee (basis points)"),
+            Msg::Spread => String::from("Spread (basis points)"),
+            Msg::FlatFee => String::from("Flat fee per trade (cents)"),
+            Msg::WithdrawalFee => String::from("Withdrawal fee (sats)"),
+            Msg::SavedScenarios => String::from("Saved scenarios"),
+            Msg::Name => String::from("Name"),
+            Msg::Save => String::from("Save"),
+            Msg::Rebalanced => String::from("Rebalanced"),
+            Msg::BuyAndHold => String::from("Buy and hold"),
+            Msg::Strategy(Strategy::LumpSum) => String::from("All at once"),
+            Msg::Strategy(Strategy::DollarCostAveraging(frequency)) => {
+                format!("Every {}", self.frequency(frequency))
+            }
+            Msg::FeePreset(name) => String::from(match name {
+                FeePresetName::NoFees => "No fees",
+                FeePresetName::LowCostExchange => "Low-cost exchange",
+                FeePresetName::BrokerageApp => "Brokerage app",
+                FeePresetName::BitcoinAtm => "Bitcoin ATM",
+                FeePresetName::Custom => "Custom fees",
+            }),
+            Msg::AllocationMode(AllocationMode::Percent) => String::from("% in bitcoin"),
+            Msg::AllocationMode(AllocationMode::Fixed) => String::from("$ in bitcoin"),
+            Msg::RebalanceRule(rule) => match rule {
+                RebalanceRule::Never => String::from("Never rebalance"),
+                RebalanceRule::Every(Period::Monthly) => String::from("Rebalance monthly"),
+                RebalanceRule::Every(Period::Quarterly) => String::from("Rebalance quarterly"),
+                RebalanceRule::Every(Period::Yearly) => String::from("Rebalance yearly"),
+                RebalanceRule::Drift(points) => format!("Rebalance at {points}% drift"),
+            },
+            Msg::CostBasisMethod(method) => String::from(match method {
+                CostBasisMethod::Fifo => "First in, first out",
+                CostBasisMethod::Lifo => "Last in, first out",
+                CostBasisMethod::Hifo => "Highest cost first",
+            }),
+            Msg::TaxRules(rules) if rules.is_none() => String::from("No tax"),
+            Msg::TaxRules(rules) => rules.name.clone(),
+            Msg::BitcoinUnit(unit) => String::from(match unit {
+                BitcoinUnit::Auto => "Auto",
+                BitcoinUnit::Btc => "BTC",
+                BitcoinUnit::MilliBtc => "mBTC",
+                BitcoinUnit::Bits => "bits (μBTC)",
+                BitcoinUnit::Sats => "sats",
+                BitcoinUnit::IntegerBtc => "₿ (integer sats)",
+            }),
+            Msg::Dollars(dollars) => String::from(match dollars {
+                Dollars::Nominal => "Nominal dollars",
+                Dollars::OfBuyDate => "Buy date dollars",
+                Dollars::OfEvaluationDate => "Sell date dollars",
+            }),
+            Msg::ThemeMode(mode) => String::from(match mode {
+                ThemeMode::Light => "Light theme",
+                ThemeMode::Dark => "Dark theme",
+                ThemeMode::System => "System theme",
+            }),
+            Msg::Language(Some(language)) => language.to_string(),
+            Msg::Language(None) => String::from("System language"),
+            Msg::Price => String::from("Price"),
+            Msg::Value => String::from("Value"),
+            Msg::Invested => String::from("Invested"),
+            Msg::InvestedWithInflation => String::from("Invested + inflation"),
+            Msg::Gain => String::from("Gain"),
+            Msg::Multiple => String::from("Multiple"),
+            Msg::Buy => String::from("Buy"),
+            Msg::Sell => String::from("Sell"),
+            Msg::LogScale => String::from("Log scale"),
+            Msg::ChartPoint(day, value) => format!("{}: {}", date(day), amount(value)),
+            Msg::InvestedAmount(invested) => format!("Invested: {}", amount(invested)),
+            Msg::CostBasis(price) => format!("Average cost: {}", self.price(price)),
+            Msg::ValueAmount(value) => format!("Value: {}", amount(value)),
+            Msg::GainAmount(gain) => format!("Gain: {}", self.signed_amount(gain)),
+            Msg::TotalReturn(ratio) => format!("Total return: {}", self.percent(ratio)),
+            Msg::YearlyGrowth(ratio) => format!("Yearly growth rate: {}", self.percent(ratio)),
+            Msg::Volatility(ratio) => {
+                format!("Volatility: {}% a year", self.decimal(ratio * 100.0, 1))
+            }
+            Msg::MaxDrawdown { loss, peak, trough } => format!(
+                "Maximum drawdown: {} from {} to {}",
+                self.percent(-loss),
+                date(peak),
+                date(trough)
+            ),
+            Msg::BestDay(day, ratio) => {
+                format!("Best day: {} on {}", self.percent(ratio), date(day))
+            }
+            Msg::WorstDay(day, ratio) => {
+                format!("Worst day: {} on {}", self.percent(ratio), date(day))
+            }
+            Msg::SharpeRatio(Some(ratio)) => {
+                format!("Sharpe ratio: {}, with", self.decimal(ratio, 2))
+            }
+            Msg::SharpeRatio(None) => String::from("Sharpe ratio with"),
+            Msg::RiskFreeRate => String::from("% risk-free rate"),
+            Msg::PercentSold => String::from("% sold"),
+            Msg::TaxEstimate {
+                short_term_gain,
+                long_term_gain,
+                tax,
+                after_tax_value,
+            } => format!(
+                "{} short term and {} long term gains: {} in tax, leaving {}",
+                self.signed_amount(short_term_gain),
+                self.signed_amount(long_term_gain),
+                amount(tax),
+                amount(after_tax_value)
+            ),
+            Msg::SavingsRate => String::from("% savings rate"),
+            Msg::Bitcoin => String::from("Bitcoin"),
+            Msg::Benchmark(Benchmark::Cash) => String::from("Cash"),
+            Msg::Benchmark(Benchmark::Savings { rate_bps }) => format!(
+                "Savings at {}%",
+                self.rate(rate_bps)
+            ),
+            Msg::Benchmark(Benchmark::Asset(asset)) => asset.name.clone(),
+            Msg::RebalancedValues {
+                rebalanced,
+                rebalances,
+                buy_and_hold,
+            } => format!(
+                "{} after {rebalances} {}, vs {} by buying and holding",
+                amount(rebalanced),
+                match self.is_singular(rebalances as u64) {
+                    true => "rebalance",
+                    false => "rebalances",
+                },
+                amount(buy_and_hold)
+            ),
+        }
+    }
+
+    fn french(self, msg: Msg) -> String {
+        let amount = |amount| self.amount(amount);
+        let date = |date| self.date(date);
+        match msg {
+            Msg::Title(Some(summary)) => format!("Et si... {summary}"),
+            Msg::Title(None) => String::from("Et si..."),
+            Msg::BuyDate => String::from("Date d'achat"),
+            Msg::SellDate => String::from("Date de vente"),
+            Msg::DateFormat => String::from("JJ/MM/AAAA"),
+            Msg::Today => String::from("Aujourd'hui"),
+            Msg::Occasion(occasion) => match occasion {
+                Occasion::Halving(number) => {
+                    match ["Premier", "Deuxième", "Troisième", "Quatrième"]
+                        .get(number.wrapping_sub(1))
+                    {
+                        Some(ordinal) => format!("{ordinal} halving"),
+                        None => format!("Halving n°{number}"),
+                    }
+                }
+                Occasion::YearlyHigh(year) => format!("Plus haut de {year}"),
+                Occasion::YearlyLow(year) => format!("Plus bas de {year}"),
+                Occasion::YearsAgo(years) => match self.is_singular(years.into()) {
+                    true => format!("Il y a {years} an"),
+                    false => format!("Il y a {years} ans"),
+                },
+            },
+            Msg::DatePreset(preset) => format!(
+                "{} ({})",
+                self.french(Msg::Occasion(preset.occasion)),
+                date(preset.date)
+            ),
+            Msg::NotableDates => String::from("Dates marquantes"),
+            Msg::TypeANumber => String::from("Saisissez un nombre"),
+            Msg::TypeAnAmount => String::from("Saisissez un montant à investir"),
+            Msg::AmountTooLarge => String::from("Ce montant est trop élevé"),
+            Msg::PickBuyDate => String::from("Choisissez une date d'achat"),
+            Msg::Loading(day) => format!("Chargement du prix du {}…", date(day)),
+            Msg::NoPrice(day, reason) => format!(
+                "Pas de prix le {}{NNBSP}: {}",
+                date(day),
+                self.unavailable(reason)
+            ),
+            Msg::FetchFailed(day, err) => format!(
+                "Impossible de récupérer le prix du {}{NNBSP}: {err}",
+                date(day)
+            ),
+            Msg::Retry => String::from("Réessayer"),
+            Msg::NoResult(err) => format!("Pas de résultat{NNBSP}: {}", self.error(err)),
+            Msg::ConvertedEverything { invested } => format!(
+                "Si vous aviez converti tout votre patrimoine de {} en",
+                amount(invested)
+            ),
+            Msg::ConvertedPart { bitcoin, invested } => format!(
+                "Si vous aviez converti {} de votre patrimoine de {} en",
+                amount(bitcoin),
+                amount(invested)
+            ),
+            Msg::BoughtOn {
+                date: day,
+                price,
+                source,
+            } => format!(
+                "le {} à {} ({})",
+                date(day),
+                self.price(price),
+                self.source(source)
+            ),
+            Msg::BoughtEvery {
+                amount: bought,
+                frequency,
+                since,
+                invested,
+            } => format!(
+                "Si vous aviez acheté {} de bitcoin chaque {} depuis le {}, vous auriez converti {} en",
+                amount(bought),
+                self.frequency(frequency),
+                date(since),
+                amount(invested)
+            ),
+            Msg::AverageCost(price) => format!("à un prix moyen de {}", self.price(price)),
+            Msg::SkippedPurchases(count) => match self.is_singular(count) {
+                true => format!("{count} achat a été sauté, faute de données de prix"),
+                false => format!("{count} achats ont été sautés, faute de données de prix"),
+            },
+            Msg::MoreThan21Million => format!(
+                "C'est plus que les 21 millions de bitcoins qui existeront jamais{NNBSP}!"
+            ),
+            Msg::ValueToday(value) => {
+                format!("Votre patrimoine serait aujourd'hui de {}", amount(value))
+            }
+            Msg::ValueOn(day, value) => format!(
+                "Votre patrimoine le {} aurait été de {}",
+                date(day),
+                amount(value)
+            ),
+            Msg::RealValue {
+                value,
+                base_year,
+                invested,
+                real_return,
+                nominal_return,
+            } => format!(
+                "{} {}, pour {} investis{NNBSP}: un rendement réel de {}, contre {} avant inflation",
+                amount(value),
+                match base_year {
+                    Some(year) => format!("en dollars de {year}"),
+                    None => String::from("en dollars d'aujourd'hui"),
+                },
+                amount(invested),
+                self.percent(real_return),
+                self.percent(nominal_return)
+            ),
+            Msg::NoCpiData { first, last } => format!(
+                "Pas de valeur corrigée de l'inflation{NNBSP}: les données de l'IPC ne couvrent que {} à {}",
+                self.month(first),
+                self.month(last)
+            ),
+            Msg::BitcoinAndCash { bitcoin, cash } => format!(
+                "{} en bitcoin et {} en espèces",
+                amount(bitcoin),
+                amount(cash)
+            ),
+            Msg::BitcoinAndAsset {
+                bitcoin,
+                rest,
+                asset,
+            } => format!(
+                "{} en bitcoin et {} en {asset}",
+                amount(bitcoin),
+                amount(rest)
+            ),
+            Msg::RestKeptInCash(asset) => format!(
+                "Pas de prix {asset} pour ces dates, le reste est donc compté en espèces"
+            ),
+            Msg::RestIn(None) => String::from("Reste en espèces"),
+            Msg::RestIn(Some(asset)) => format!("Reste en {asset}"),
+            Msg::BeforeFees { gross, fees } => format!(
+                "{} avant frais, dont {} payés en frais",
+                amount(gross),
+                amount(fees)
+            ),
+            Msg::SubSatPrecision => String::from("Précision sous le sat"),
+            Msg::BtcThreshold => String::from("Afficher en ₿ à partir de (sats)"),
+            Msg::TradingFee => String::from("Frais de transaction (points de base)"),
+            Msg::Spread => String::from("Écart de prix (points de base)"),
+            Msg::FlatFee => String::from("Frais fixes par transaction (centimes)"),
+            Msg::WithdrawalFee => String::from("Frais de retrait (sats)"),
+            Msg::SavedScenarios => String::from("Scénarios enregistrés"),
+            Msg::Name => String::from("Nom"),
+            Msg::Save => String::from("Enregistrer"),
+            Msg::Rebalanced => String::from("Rééquilibré"),
+            Msg::BuyAndHold => String::from("Achat et conservation"),
+            Msg::Strategy(Strategy::LumpSum) => String::from("En une fois"),
+            Msg::Strategy(Strategy::DollarCostAveraging(frequency)) => {
+                format!("Chaque {}", self.frequency(frequency))
+            }
+            Msg::FeePreset(name) => String::from(match name {
+                FeePresetName::NoFees => "Sans frais",
+                FeePresetName::LowCostExchange => "Plateforme à bas coût",
+                FeePresetName::BrokerageApp => "Application de courtage",
+                FeePresetName::BitcoinAtm => "Distributeur de bitcoin",
+                FeePresetName::Custom => "Frais personnalisés",
+            }),
+            Msg::AllocationMode(AllocationMode::Percent) => String::from("% en bitcoin"),
+            Msg::AllocationMode(AllocationMode::Fixed) => String::from("$ en bitcoin"),
+            Msg::RebalanceRule(rule) => match rule {
+                RebalanceRule::Never => String::from("Ne jamais rééquilibrer"),
+                RebalanceRule::Every(Period::Monthly) => String::from("Rééquilibrer chaque mois"),
+                RebalanceRule::Every(Period::Quarterly) => {
+                    String::from("Rééquilibrer chaque trimestre")
+                }
+                RebalanceRule::Every(Period::Yearly) => String::from("Rééquilibrer chaque année"),
+                RebalanceRule::Drift(points) => {
+                    format!("Rééquilibrer à {points}\u{A0}% d'écart")
+                }
+            },
+            Msg::CostBasisMethod(method) => String::from(match method {
+                CostBasisMethod::Fifo => "Premier entré, premier sorti",
+                CostBasisMethod::Lifo => "Dernier entré, premier sorti",
+                CostBasisMethod::Hifo => "Coût le plus élevé d'abord",
+            }),
+            Msg::TaxRules(rules) if rules.is_none() => String::from("Sans impôt"),
+            Msg::TaxRules(rules) => self.jurisdiction(&rules.name).to_string(),
+            Msg::BitcoinUnit(unit) => String::from(match unit {
+                BitcoinUnit::Auto => "Auto",
+                BitcoinUnit::Btc => "BTC",
+                BitcoinUnit::MilliBtc => "mBTC",
+                BitcoinUnit::Bits => "bits (μBTC)",
+                BitcoinUnit::Sats => "sats",
+                BitcoinUnit::IntegerBtc => "₿ (sats entiers)",
+            }),
+            Msg::Dollars(dollars) => String::from(match dollars {
+                Dollars::Nominal => "Dollars courants",
+                Dollars::OfBuyDate => "Dollars de la date d'achat",
+                Dollars::OfEvaluationDate => "Dollars de la date de vente",
+            }),
+            Msg::ThemeMode(mode) => String::from(match mode {
+                ThemeMode::Light => "Thème clair",
+                ThemeMode::Dark => "Thème sombre",
+                ThemeMode::System => "Thème du système",
+            }),
+            Msg::Language(Some(language)) => language.to_string(),
+            Msg::Language(None) => String::from("Langue du système"),
+            Msg::Price => String::from("Prix"),
+            Msg::Value => String::from("Valeur"),
+            Msg::Invested => String::from("Investi"),
+            Msg::InvestedWithInflation => String::from("Investi + inflation"),
+            Msg::Gain => String::from("Gain"),
+            Msg::Multiple => String::from("Multiple"),
+            Msg::Buy => String::from("Achat"),
+            Msg::Sell => String::from("Vente"),
+            Msg::LogScale => String::from("Échelle logarithmique"),
+            Msg::ChartPoint(day, value) => format!("{}{NNBSP}: {}", date(day), amount(value)),
+            Msg::InvestedAmount(invested) => format!("Investi{NNBSP}: {}", amount(invested)),
+            Msg::CostBasis(price) => format!("Coût moyen{NNBSP}: {}", self.price(price)),
+            Msg::ValueAmount(value) => format!("Valeur{NNBSP}: {}", amount(value)),
+            Msg::GainAmount(gain) => format!("Gain{NNBSP}: {}", self.signed_amount(gain)),
+            Msg::TotalReturn(ratio) => format!("Rendement total{NNBSP}: {}", self.percent(ratio)),
+            Msg::YearlyGrowth(ratio) => format!(
+                "Taux de croissance annuel{NNBSP}: {}",
+                self.percent(ratio)
+            ),
+            Msg::Volatility(ratio) => format!(
+                "Volatilité{NNBSP}: {}\u{A0}% par an",
+                self.decimal(ratio * 100.0, 1)
+            ),
+            Msg::MaxDrawdown { loss, peak, trough } => format!(
+                "Baisse maximale{NNBSP}: {} du {} au {}",
+                self.percent(-loss),
+                date(peak),
+                date(trough)
+            ),
+            Msg::BestDay(day, ratio) => format!(
+                "Meilleur jour{NNBSP}: {} le {}",
+                self.percent(ratio),
+                date(day)
+            ),
+            Msg::WorstDay(day, ratio) => format!(
+                "Pire jour{NNBSP}: {} le {}",
+                self.percent(ratio),
+                date(day)
+            ),
+            Msg::SharpeRatio(Some(ratio)) => {
+                format!("Ratio de Sharpe{NNBSP}: {}, avec", self.decimal(ratio, 2))
+            }
+            Msg::SharpeRatio(None) => String::from("Ratio de Sharpe avec"),
+            Msg::RiskFreeRate => String::from("% de taux sans risque"),
+            Msg::PercentSold => String::from("% vendus"),
+            Msg::TaxEstimate {
+                short_term_gain,
+                long_term_gain,
+                tax,
+                after_tax_value,
+            } => format!(
+                "{} de plus-values à court terme et {} à long terme{NNBSP}: {} d'impôt, il reste {}",
+                self.signed_amount(short_term_gain),
+                self.signed_amount(long_term_gain),
+                amount(tax),
+                amount(after_tax_value)
+            ),
+            Msg::SavingsRate => String::from("% de taux d'épargne"),
+            Msg::Bitcoin => String::from("Bitcoin"),
+            Msg::Benchmark(Benchmark::Cash) => String::from("Espèces"),
+            Msg::Benchmark(Benchmark::Savings { rate_bps }) => format!(
+                "Épargne à {}\u{A0}%",
+                self.rate(rate_bps)
+            ),
+            Msg::Benchmark(Benchmark::Asset(asset)) => asset.name.clone(),
+            Msg::RebalancedValues {
+                rebalanced,
+                rebalances,
+                buy_and_hold,
+            } => format!(
+                "{} après {rebalances} {}, contre {} en achetant et conservant",
+                amount(rebalanced),
+                match self.is_singular(rebalances as u64) {
+                    true => "rééquilibrage",
+                    false => "rééquilibrages",
+                },
+                amount(buy_and_hold)
+            ),
+        }
+    }
+
+    fn spanish(self, msg: Msg) -> String {
+        let amount = |amount| self.amount(amount);
+        let date = |date| self.date(date);
+        match msg {
+            Msg::Title(Some(summary)) => format!("¿Y si...? {summary}"),
+            Msg::Title(None) => String::from("¿Y si...?"),
+            Msg::BuyDate => String::from("Fecha de compra"),
+            Msg::SellDate => String::from("Fecha de venta"),
+            Msg::DateFormat => String::from("DD/MM/AAAA"),
+            Msg::Today => String::from("Hoy"),
+            Msg::Occasion(occasion) => match occasion {
+                Occasion::Halving(number) => {
+                    match ["Primer", "Segundo", "Tercer", "Cuarto"].get(number.wrapping_sub(1)) {
+                        Some(ordinal) => format!("{ordinal} halving"),
+                        None => format!("Halving n.º {number}"),
+                    }
+                }
+                Occasion::YearlyHigh(year) => format!("Máximo de {year}"),
+                Occasion::YearlyLow(year) => format!("Mínimo de {year}"),
+                Occasion::YearsAgo(years) => match self.is_singular(years.into()) {
+                    true => format!("Hace {years} año"),
+                    false => format!("Hace {years} años"),
+                },
+            },
+            Msg::DatePreset(preset) => format!(
+                "{} ({})",
+                self.spanish(Msg::Occasion(preset.occasion)),
+                date(preset.date)
+            ),
+            Msg::NotableDates => String::from("Fechas destacadas"),
+            Msg::TypeANumber => String::from("Introduce un número"),
+            Msg::TypeAnAmount => String::from("Introduce una cantidad a invertir"),
+            Msg::AmountTooLarge => String::from("Esa cantidad es demasiado grande"),
+            Msg::PickBuyDate => String::from("Elige una fecha de compra"),
+            Msg::Loading(day) => format!("Cargando el precio del {}…", date(day)),
+            Msg::NoPrice(day, reason) => format!(
+                "No hay precio el {}: {}",
+                date(day),
+                self.unavailable(reason)
+            ),
+            Msg::FetchFailed(day, err) => {
+                format!("No se pudo obtener el precio del {}: {err}", date(day))
+            }
+            Msg::Retry => String::from("Reintentar"),
+            Msg::NoResult(err) => format!("Sin resultado: {}", self.error(err)),
+            Msg::ConvertedEverything { invested } => format!(
+                "Si hubieras convertido todo tu patrimonio de {} en",
+                amount(invested)
+            ),
+            Msg::ConvertedPart { bitcoin, invested } => format!(
+                "Si hubieras convertido {} de tu patrimonio de {} en",
+                amount(bitcoin),
+                amount(invested)
+            ),
+            Msg::BoughtOn {
+                date: day,
+                price,
+                source,
+            } => format!(
+                "el {} a {} ({})",
+                date(day),
+                self.price(price),
+                self.source(source)
+            ),
+            Msg::BoughtEvery {
+                amount: bought,
+                frequency,
+                since,
+                invested,
+            } => format!(
+                "Si hubieras comprado {} de bitcoin cada {} desde el {}, habrías convertido {} en",
+                amount(bought),
+                self.frequency(frequency),
+                date(since),
+                amount(invested)
+            ),
+            Msg::AverageCost(price) => format!("a un precio medio de {}", self.price(price)),
+            Msg::SkippedPurchases(count) => match self.is_singular(count) {
+                true => format!("Se omitió {count} compra por falta de datos de precio"),
+                false => format!("Se omitieron {count} compras por falta de datos de precio"),
+            },
+            Msg::MoreThan21Million => String::from(
+                "¡Eso es más que los 21 millones de bitcoins que existirán jamás!",
+            ),
+            Msg::ValueToday(value) => format!("Tu patrimonio hoy sería de {}", amount(value)),
+            Msg::ValueOn(day, value) => format!(
+                "Tu patrimonio el {} habría sido de {}",
+                date(day),
+                amount(value)
+            ),
+            Msg::RealValue {
+                value,
+                base_year,
+                invested,
+                real_return,
+                nominal_return,
+            } => format!(
+                "{} {}, por {} invertidos: una rentabilidad real del {}, frente al {} antes de la inflación",
+                amount(value),
+                match base_year {
+                    Some(year) => format!("en dólares de {year}"),
+                    None => String::from("en dólares de hoy"),
+                },
+                amount(invested),
+                self.percent(real_return),
+                self.percent(nominal_return)
+            ),
+            Msg::NoCpiData { first, last } => format!(
+                "Sin valor ajustado por inflación: los datos del IPC solo cubren de {} a {}",
+                self.month(first),
+                self.month(last)
+            ),
+            Msg::BitcoinAndCash { bitcoin, cash } => format!(
+                "{} en bitcoin y {} en efectivo",
+                amount(bitcoin),
+                amount(cash)
+            ),
+            Msg::BitcoinAndAsset {
+                bitcoin,
+                rest,
+                asset,
+            } => format!(
+                "{} en bitcoin y {} en {asset}",
+                amount(bitcoin),
+                amount(rest)
+            ),
+            Msg::RestKeptInCash(asset) => format!(
+                "No hay precios de {asset} para estas fechas, así que el resto se cuenta como efectivo"
+            ),
+            Msg::RestIn(None) => String::from("Resto en efectivo"),
+            Msg::RestIn(Some(asset)) => format!("Resto en {asset}"),
+            Msg::BeforeFees { gross, fees } => format!(
+                "{} antes de comisiones, {} pagados en comisiones",
+                amount(gross),
+                amount(fees)
+            ),
+            Msg::SubSatPrecision => String::from("Precisión inferior al sat"),
+            Msg::BtcThreshold => String::from("Mostrar en ₿ desde (sats)"),
+            Msg::TradingFee => String::from("Comisión de operación (puntos básicos)"),
+            Msg::Spread => String::from("Diferencial (puntos básicos)"),
+            Msg::FlatFee => String::from("Comisión fija por operación (centavos)"),
+            Msg::WithdrawalFee => String::from("Comisión de retiro (sats)"),
+            Msg::SavedScenarios => String::from("Escenarios guardados"),
+            Msg::Name => String::from("Nombre"),
+            Msg::Save => String::from("Guardar"),
+            Msg::Rebalanced => String::from("Reequilibrado"),
+            Msg::BuyAndHold => String::from("Comprar y mantener"),
+            Msg::Strategy(Strategy::LumpSum) => String::from("De una vez"),
+            Msg::Strategy(Strategy::DollarCostAveraging(frequency)) => {
+                format!("Cada {}", self.frequency(frequency))
+            }
+            Msg::FeePreset(name) => String::from(match name {
+                FeePresetName::NoFees => "Sin comisiones",
+                FeePresetName::LowCostExchange => "Exchange de bajo coste",
+                FeePresetName::BrokerageApp => "App de corretaje",
+                FeePresetName::BitcoinAtm => "Cajero de bitcoin",
+                FeePresetName::Custom => "Comisiones personalizadas",
+            }),
+            Msg::AllocationMode(AllocationMode::Percent) => String::from("% en bitcoin"),
+            Msg::AllocationMode(AllocationMode::Fixed) => String::from("US$ en bitcoin"),
+            Msg::RebalanceRule(rule) => match rule {
+                RebalanceRule::Never => String::from("No reequilibrar nunca"),
+                RebalanceRule::Every(Period::Monthly) => String::from("Reequilibrar cada mes"),
+                RebalanceRule::Every(Period::Quarterly) => {
+                    String::from("Reequilibrar cada trimestre")
+                }
+                RebalanceRule::Every(Period::Yearly) => String::from("Reequilibrar cada año"),
+                RebalanceRule::Drift(points) => {
+                    format!("Reequilibrar con {points}\u{A0}% de desviación")
+                }
+            },
+            Msg::CostBasisMethod(method) => String::from(match method {
+                CostBasisMethod::Fifo => "Primero en entrar, primero en salir",
+                CostBasisMethod::Lifo => "Último en entrar, primero en salir",
+                CostBasisMethod::Hifo => "Mayor coste primero",
+            }),
+            Msg::TaxRules(rules) if rules.is_none() => String::from("Sin impuestos"),
+            Msg::TaxRules(rules) => self.jurisdiction(&rules.name).to_string(),
+            Msg::BitcoinUnit(unit) => String::from(match unit {
+                BitcoinUnit::Auto => "Automático",
+                BitcoinUnit::Btc => "BTC",
+                BitcoinUnit::MilliBtc => "mBTC",
+                BitcoinUnit::Bits => "bits (μBTC)",
+                BitcoinUnit::Sats => "sats",
+                BitcoinUnit::IntegerBtc => "₿ (sats enteros)",
+            }),
+            Msg::Dollars(dollars) => String::from(match dollars {
+                Dollars::Nominal => "Dólares corrientes",
+                Dollars::OfBuyDate => "Dólares de la fecha de compra",
+                Dollars::OfEvaluationDate => "Dólares de la fecha de venta",
+            }),
+            Msg::ThemeMode(mode) => String::from(match mode {
+                ThemeMode::Light => "Tema claro",
+                ThemeMode::Dark => "Tema oscuro",
+                ThemeMode::System => "Tema del sistema",
+            }),
+            Msg::Language(Some(language)) => language.to_string(),
+            Msg::Language(None) => String::from("Idioma del sistema"),
+            Msg::Price => String::from("Precio"),
+            Msg::Value => String::from("Valor"),
+            Msg::Invested => String::from("Invertido"),
+            Msg::InvestedWithInflation => String::from("Invertido + inflación"),
+            Msg::Gain => String::from("Ganancia"),
+            Msg::Multiple => String::from("Múltiplo"),
+            Msg::Buy => String::from("Compra"),
+            Msg::Sell => String::from("Venta"),
+            Msg::LogScale => String::from("Escala logarítmica"),
+            Msg::ChartPoint(day, value) => format!("{}: {}", date(day), amount(value)),
+            Msg::InvestedAmount(invested) => format!("Invertido: {}", amount(invested)),
+            Msg::CostBasis(price) => format!("Coste medio: {}", self.price(price)),
+            Msg::ValueAmount(value) => format!("Valor: {}", amount(value)),
+            Msg::GainAmount(gain) => format!("Ganancia: {}", self.signed_amount(gain)),
+            Msg::TotalReturn(ratio) => format!("Rentabilidad total: {}", self.percent(ratio)),
+            Msg::YearlyGrowth(ratio) => {
+                format!("Tasa de crecimiento anual: {}", self.percent(ratio))
+            }
+            Msg::Volatility(ratio) => format!(
+                "Volatilidad: {}\u{A0}% al año",
+                self.decimal(ratio * 100.0, 1)
+            ),
+            Msg::MaxDrawdown { loss, peak, trough } => format!(
+                "Caída máxima: {} del {} al {}",
+                self.percent(-loss),
+                date(peak),
+                date(trough)
+            ),
+            Msg::BestDay(day, ratio) => {
+                format!("Mejor día: {} el {}", self.percent(ratio), date(day))
+            }
+            Msg::WorstDay(day, ratio) => {
+                format!("Peor día: {} el {}", self.percent(ratio), date(day))
+            }
+            Msg::SharpeRatio(Some(ratio)) => {
+                format!("Ratio de Sharpe: {}, con", self.decimal(ratio, 2))
+            }
+            Msg::SharpeRatio(None) => String::from("Ratio de Sharpe con"),
+            Msg::RiskFreeRate => String::from("% de tasa libre de riesgo"),
+            Msg::PercentSold => String::from("% vendido"),
+            Msg::TaxEstimate {
+                short_term_gain,
+                long_term_gain,
+                tax,
+                after_tax_value,
+            } => format!(
+                "{} de ganancias a corto plazo y {} a largo plazo: {} de impuestos, quedan {}",
+                self.signed_amount(short_term_gain),
+                self.signed_amount(long_term_gain),
+                amount(tax),
+                amount(after_tax_value)
+            ),
+            Msg::SavingsRate => String::from("% de tasa de ahorro"),
+            Msg::Bitcoin => String::from("Bitcoin"),
+            Msg::Benchmark(Benchmark::Cash) => String::from("Efectivo"),
+            Msg::Benchmark(Benchmark::Savings { rate_bps }) => format!(
+                "Ahorro al {}\u{A0}%",
+                self.rate(rate_bps)
+            ),
+            Msg::Benchmark(Benchmark::Asset(asset)) => asset.name.clone(),
+            Msg::RebalancedValues {
+                rebalanced,
+                rebalances,
+                buy_and_hold,
+            } => format!(
+                "{} tras {rebalances} {}, frente a {} comprando y manteniendo",
+                amount(rebalanced),
+                match self.is_singular(rebalances as u64) {
+                    true => "reequilibrio",
+                    false => "reequilibrios",
+                },
+                amount(buy_and_hold)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price_lookup::test_prices::date;
+
+    #[test]
+    fn test_from_tag() {
+        assert_eq!(Language::from_tag("fr-FR"), Some(Language::French));
+        assert_eq!(Language::from_tag("es_ES.UTF-8"), Some(Language::Spanish));
+        assert_eq!(Language::from_tag("EN"), Some(Language::English));
+        assert_eq!(Language::from_tag("de-DE"), None);
+        assert_eq!(Language::from_tag("C"), None);
+    }
+
+    #[test]
+    fn test_formats() {
+        let amount = DollarAmount::from_cents(123_456_789);
+        assert_eq!(Language::English.amount(amount), "$1,234,567.89");
+        assert_eq!(
+            Language::French.amount(amount),
+            "1\u{202F}234\u{202F}567,89\u{A0}$"
+        );
+        assert_eq!(Language::Spanish.amount(amount), "1.234.567,89\u{A0}US$");
+
+        assert_eq!(Language::English.percent(0.125), "+12.5%");
+        assert_eq!(Language::French.percent(-0.5), "-50,0\u{A0}%");
+
+        assert_eq!(Language::English.date(date(2024, 3, 6)), "2024-03-06");
+        assert_eq!(Language::Spanish.date(date(2024, 3, 6)), "06/03/2024");
+        assert_eq!(Language::French.month(date(2024, 2, 1)), "février 2024");
+        let halving = DatePreset {
+            occasion: Occasion::Halving(1),
+            date: date(2012, 11, 28),
+        };
+        assert_eq!(
+            Language::French.tr(Msg::DatePreset(halving)),
+            "Premier halving (28/11/2012)"
+        );
+        assert_eq!(
+            Language::English.tr(Msg::Occasion(Occasion::Halving(5))),
+            "Halving no. 5"
+        );
+        assert_eq!(
+            Language::Spanish.tr(Msg::NoResult(scenario::Error::MissingPrice(date(
+                2024, 3, 6
+            )))),
+            "Sin resultado: no hay precio disponible el 06/03/2024"
+        );
+
+        for language in Language::ALL {
+            let day = date(2024, 3, 6);
+            assert_eq!(language.parse_date(&language.date(day)), Some(day));
+            assert_eq!(language.parse_date(" 2024-03-06"), Some(day));
+        }
+        assert_eq!(Language::English.parse_date("06/03/2024"), None);
+        assert_eq!(Language::French.parse_date("31/02/2024"), None);
+    }
+
+    #[test]
+    fn test_plurals() {
+        let skipped = |language: Language, count| language.tr(Msg::SkippedPurchases(count));
+        assert_eq!(
+            skipped(Language::English, 1),
+            "1 purchase was skipped, for lack of price data"
+        );
+        assert_eq!(
+            skipped(Language::English, 0),
+            "0 purchases were skipped, for lack of price data"
+        );
+        assert_eq!(
+            skipped(Language::French, 0),
+            "0 achat a été sauté, faute de données de prix"
+        );
+        assert_eq!(
+            skipped(Language::Spanish, 3),
+            "Se omitieron 3 compras por falta de datos de precio"
+        );
+        assert_eq!(
+            Language::French.tr(Msg::Occasion(Occasion::YearsAgo(5))),
+            "Il y a 5 ans"
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        let savings = |rate_bps| Msg::Benchmark(Benchmark::Savings { rate_bps });
+        assert_eq!(Language::English.tr(savings(500)), "Savings at 5%");
+        assert_eq!(Language::French.tr(savings(525)), "Épargne à 5,25\u{A0}%");
+        assert_eq!(Language::Spanish.multiple(2.5), "x2,50");
+        assert_eq!(
+            Language::English.tr(Msg::GainAmount(SignedDollarAmount::from_cents(-150))),
+            "Gain: -$1.50"
+        );
+    }
+}
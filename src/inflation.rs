@@ -0,0 +1,326 @@
+//! Consumer Price Index for All Urban Consumers (CPI-U, not seasonally
+//! adjusted) from the U.S. Bureau of Labor Statistics, to compare dollars from
+//! different dates. The bundled CSV has monthly observations, each dated on
+//! the first of its month: the index is interpolated linearly between them,
+//! and unknown after the month of the last one rather than guessed.
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+use chrono::{Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+    dollar::{DollarAmount, SignedDollarAmount},
+    metrics::DailyValue,
+    price::{mul_div, Rounding},
+    scenario::{Scenario, ScenarioResult},
+};
+
+// Makes it work more easily on WASM + other platforms
+const CPI_HISTORY: &[u8] = include_bytes!("../data/cpi.csv");
+
+#[derive(Deserialize)]
+//"Date","CPI"
+struct CsvRecord {
+    #[serde(rename = "Date")]
+    date: NaiveDate,
+    #[serde(deserialize_with = "deserialize_index", rename = "CPI")]
+    index: u64,
+}
+
+/// Index in thousandths of a point, e.g. 230.280 is 230280
+fn deserialize_index<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let index: f64 = Deserialize::deserialize(deserializer)?;
+    Ok((index * 1000.0).round() as u64)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Cpi {
+    /// Thousandths of a point, by observation date
+    index: BTreeMap<NaiveDate, u64>,
+}
+
+impl Cpi {
+    pub fn from_csv(reader: impl std::io::Read) -> Result<Self, csv::Error> {
+        let mut reader = csv::Reader::from_reader(reader);
+        let mut index = BTreeMap::new();
+        for record in reader.deserialize() {
+            let record: CsvRecord = record?;
+            index.insert(record.date, record.index);
+        }
+
+        Ok(Self { index })
+    }
+
+    /// The bundled series, or an empty one if it can't be read so values are
+    /// just shown in nominal terms.
+    pub fn bundled() -> Self {
+        Self::from_csv(CPI_HISTORY).unwrap_or_default()
+    }
+
+    /// Months of the first and last observations, outside of which there's
+    /// no index
+    pub fn months(&self) -> Option<RangeInclusive<NaiveDate>> {
+        Some(*self.index.keys().next()?..=*self.index.keys().next_back()?)
+    }
+
+    /// Index on `date`, in thousandths of a point. `None` before the first
+    /// observation, and after the month of the last one.
+    pub fn index(&self, date: NaiveDate) -> Option<u64> {
+        let (&before_date, &before) = self.index.range(..=date).next_back()?;
+        let Some((&after_date, &after)) = self.index.range(date..).next() else {
+            let same_month =
+                (before_date.year(), before_date.month()) == (date.year(), date.month());
+            return same_month.then_some(before);
+        };
+        if after_date == before_date {
+            return Some(before);
+        }
+
+        let elapsed = (date - before_date).num_days();
+        let span = (after_date - before_date).num_days();
+        let change = (after as i64 - before as i64) * elapsed / span;
+        u64::try_from(before as i64 + change).ok()
+    }
+
+    /// `amount` in dollars of `from`, expressed in dollars of `to`.
+    pub fn convert(
+        &self,
+        amount: DollarAmount,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Option<DollarAmount> {
+        mul_div(
+            amount.cents(),
+            self.index(to)?,
+            self.index(from)?,
+            Rounding::Nearest,
+        )
+        .map(DollarAmount::from_cents)
+    }
+
+    /// What everything put in so far would need to be worth on each day, just
+    /// to keep up with inflation. `None` if there's no index for some day.
+    pub fn invested_with_inflation(
+        &self,
+        values: &[DailyValue],
+    ) -> Option<Vec<(NaiveDate, DollarAmount)>> {
+        // Contributions in index points, so each day is a single conversion
+        let mut points = 0.0;
+        values
+            .iter()
+            .map(|value| {
+                let index = self.index(value.date)? as f64;
+                points += value.contribution.cents() as f64 / index;
+                Some((value.date, DollarAmount::from(points * index / 100.0)))
+            })
+            .collect()
+    }
+
+    /// Result of a scenario with everything in dollars of `base_date`. Each
+    /// contribution is converted from the date it was made.
+    pub fn real_result(
+        &self,
+        scenario: &Scenario,
+        result: &ScenarioResult,
+        base_date: NaiveDate,
+    ) -> Option<RealResult> {
+        let invested = result
+            .acquisition
+            .purchases
+            .iter()
+            .map(|purchase| self.convert(scenario.amount, purchase.date, base_date))
+            .try_fold(DollarAmount::default(), |total, amount| {
+                total.checked_add(amount?)
+            })?;
+        let value = self.convert(result.value, scenario.evaluation_date, base_date)?;
+
+        Some(RealResult {
+            base_date,
+            invested,
+            value,
+            gain: value.signed_sub(invested)?,
+            multiple: value.ratio(invested)?,
+        })
+    }
+}
+
+/// Which dollars amounts are shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dollars {
+    /// As they were at the time, without adjusting for inflation
+    #[default]
+    Nominal,
+    /// Adjusted to what they were worth on the buy date
+    OfBuyDate,
+    /// Adjusted to what they were worth on the evaluation date
+    OfEvaluationDate,
+}
+
+impl Dollars {
+    pub const ALL: [Dollars; 3] = [
+        Dollars::Nominal,
+        Dollars::OfBuyDate,
+        Dollars::OfEvaluationDate,
+    ];
+
+    pub fn base_date(self, scenario: &Scenario) -> Option<NaiveDate> {
+        match self {
+            Dollars::Nominal => None,
+            Dollars::OfBuyDate => Some(scenario.buy_date),
+            Dollars::OfEvaluationDate => Some(scenario.evaluation_date),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RealResult {
+    /// Date whose dollars everything is expressed in
+    pub base_date: NaiveDate,
+    pub invested: DollarAmount,
+    pub value: DollarAmount,
+    pub gain: SignedDollarAmount,
+    /// `value / invested` once adjusted for inflation
+    pub multiple: f64,
+}
+
+impl RealResult {
+    /// Year of the dollars the result is in, or `None` for today's dollars
+    pub fn base_year(&self) -> Option<i32> {
+        (self.base_date != Utc::now().date_naive()).then(|| self.base_date.year())
+    }
+
+    /// e.g. "in 2013 dollars", or "in today's dollars"
+    pub fn dollars(&self) -> String {
+        match self.base_year() {
+            Some(year) => format!("in {year} dollars"),
+            None => String::from("in today's dollars"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fees::FeeModel,
+        price_lookup::test_prices::{date, history},
+        scenario::{Allocation, Frequency, Strategy},
+    };
+
+    const CPI: &str = "Date,CPI\n2020-01-01,100.000\n2021-01-01,110.000\n2022-01-01,132.000\n";
+
+    #[test]
+    fn test_index() {
+        let cpi = Cpi::from_csv(CPI.as_bytes()).unwrap();
+        assert_eq!(cpi.index(date(2019, 12, 31)), None);
+        assert_eq!(cpi.index(date(2020, 1, 1)), Some(100_000));
+        // Halfway through 2021 (181 of 365 days)
+        assert_eq!(cpi.index(date(2021, 7, 1)), Some(120_909));
+        // The last observation is for its whole month, and nothing is known
+        // after that
+        assert_eq!(cpi.index(date(2022, 1, 31)), Some(132_000));
+        assert_eq!(cpi.index(date(2022, 2, 1)), None);
+        assert_eq!(cpi.months(), Some(date(2020, 1, 1)..=date(2022, 1, 1)));
+
+        assert_eq!(
+            cpi.convert(DollarAmount::from(100), date(2020, 1, 1), date(2022, 1, 1)),
+            Some(DollarAmount::from(132))
+        );
+        assert_eq!(
+            cpi.convert(DollarAmount::from(132), date(2022, 1, 1), date(2020, 1, 1)),
+            Some(DollarAmount::from(100))
+        );
+        assert_eq!(
+            cpi.convert(DollarAmount::from(100), date(2019, 1, 1), date(2020, 1, 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_real_result() {
+        let cpi = Cpi::from_csv(CPI.as_bytes()).unwrap();
+        let prices = history(&[
+            (date(2020, 1, 1), 10_000),
+            (date(2021, 1, 1), 10_000),
+            (date(2022, 1, 1), 11_000),
+        ]);
+        let mut scenario = Scenario {
+            amount: DollarAmount::from(1_000),
+            allocation: Allocation::default(),
+            buy_date: date(2020, 1, 1),
+            evaluation_date: date(2022, 1, 1),
+            strategy: Strategy::LumpSum,
+            fees: FeeModel::default(),
+        };
+
+        // Up 10%, while prices went up 32%
+        let result = scenario.evaluate(&prices).unwrap();
+        let real = cpi
+            .real_result(&scenario, &result, date(2020, 1, 1))
+            .unwrap();
+        assert_eq!(real.invested, DollarAmount::from(1_000));
+        assert_eq!(real.value, DollarAmount::from_cents(83_333));
+        assert_eq!(real.gain.to_string(), "-$166.67");
+        assert_eq!(real.dollars(), "in 2020 dollars");
+
+        let real = cpi
+            .real_result(&scenario, &result, date(2022, 1, 1))
+            .unwrap();
+        assert_eq!(real.invested, DollarAmount::from(1_320));
+        assert_eq!(real.value, DollarAmount::from(1_100));
+
+        // $1,000 of 2020, then $1,000 of 2021, in 2022 dollars
+        scenario.strategy = Strategy::DollarCostAveraging(Frequency::Monthly);
+        scenario.evaluation_date = date(2021, 1, 1);
+        let result = scenario.evaluate(&prices).unwrap();
+        let real = cpi
+            .real_result(&scenario, &result, date(2022, 1, 1))
+            .unwrap();
+        assert_eq!(real.invested, DollarAmount::from(2_520));
+    }
+
+    #[test]
+    fn test_invested_with_inflation() {
+        let cpi = Cpi::from_csv(CPI.as_bytes()).unwrap();
+        let value = |date, contribution: u32| DailyValue {
+            date,
+            value: DollarAmount::default(),
+            invested: DollarAmount::default(),
+            contribution: DollarAmount::from(contribution),
+        };
+
+        let invested = cpi
+            .invested_with_inflation(&[
+                value(date(2020, 1, 1), 100),
+                value(date(2021, 1, 1), 100),
+                value(date(2022, 1, 1), 0),
+            ])
+            .unwrap();
+        assert_eq!(
+            invested,
+            vec![
+                (date(2020, 1, 1), DollarAmount::from(100)),
+                (date(2021, 1, 1), DollarAmount::from(210)),
+                (date(2022, 1, 1), DollarAmount::from(252)),
+            ]
+        );
+
+        assert_eq!(
+            cpi.invested_with_inflation(&[value(date(2019, 1, 1), 100)]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_loading_csv() {
+        let cpi = Cpi::bundled();
+        assert!(cpi.index(date(2013, 1, 1)).is_some());
+        assert_eq!(cpi.index(date(2009, 1, 1)), None);
+        // Monthly
+        assert_eq!(cpi.index(date(2021, 6, 1)), Some(271_696));
+        assert_eq!(cpi.index(date(2021, 7, 1)), Some(273_003));
+    }
+}
@@ -0,0 +1,146 @@
+//! Keyboard shortcuts on a text input for `iced`, e.g. the arrow keys to step
+//! a number. Keys are only handled while the input has the focus, before the
+//! input itself sees them.
+use iced::{
+    advanced::{
+        layout, renderer,
+        widget::{tree, Operation, Tree},
+        Clipboard, Layout, Shell, Widget,
+    },
+    event,
+    keyboard::{self, Key, Modifiers},
+    mouse,
+    widget::{text_input, TextInput},
+    Element, Event, Length, Rectangle, Renderer, Size, Theme,
+};
+
+type InputState = text_input::State<<Renderer as iced::advanced::text::Renderer>::Paragraph>;
+type OnKey<'a, Message> = Box<dyn Fn(&Key, Modifiers) -> Option<Message> + 'a>;
+
+pub struct KeyBindings<'a, Message> {
+    input: TextInput<'a, Message>,
+    on_key: OnKey<'a, Message>,
+}
+
+/// `on_key` returns the message for the keys it handles, and `None` for
+/// those the input should handle as usual.
+pub fn key_bindings<'a, Message>(
+    input: TextInput<'a, Message>,
+    on_key: impl Fn(&Key, Modifiers) -> Option<Message> + 'a,
+) -> KeyBindings<'a, Message> {
+    KeyBindings {
+        input,
+        on_key: Box::new(on_key),
+    }
+}
+
+impl<'a, Message: Clone> Widget<Message, Theme, Renderer> for KeyBindings<'a, Message> {
+    fn size(&self) -> Size<Length> {
+        Widget::size(&self.input)
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        Widget::layout(&self.input, tree, renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        Widget::draw(
+            &self.input,
+            tree,
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        )
+    }
+
+    fn tag(&self) -> tree::Tag {
+        Widget::tag(&self.input)
+    }
+
+    fn state(&self) -> tree::State {
+        Widget::state(&self.input)
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        Widget::diff(&self.input, tree)
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation<Message>,
+    ) {
+        Widget::operate(&self.input, tree, layout, renderer, operation)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        if let Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = &event {
+            let is_focused = tree.state.downcast_ref::<InputState>().is_focused();
+            if let Some(message) = is_focused.then(|| (self.on_key)(key, *modifiers)).flatten() {
+                shell.publish(message);
+                return event::Status::Captured;
+            }
+        }
+
+        Widget::on_event(
+            &mut self.input,
+            tree,
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        Widget::mouse_interaction(&self.input, tree, layout, cursor, viewport, renderer)
+    }
+}
+
+impl<'a, Message> From<KeyBindings<'a, Message>> for Element<'a, Message>
+where
+    Message: Clone + 'a,
+{
+    fn from(key_bindings: KeyBindings<'a, Message>) -> Self {
+        Element::new(key_bindings)
+    }
+}
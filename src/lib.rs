@@ -0,0 +1,31 @@
+pub mod a11y;
+pub mod benchmark;
+pub mod bitcoin;
+pub mod bitcoin_text;
+pub mod chart;
+pub mod date_picker;
+pub mod date_presets;
+pub mod dollar;
+pub mod fees;
+pub mod historical_data;
+pub mod i18n;
+pub mod inflation;
+pub mod key_bindings;
+pub mod metrics;
+pub mod numeric_input;
+pub mod preferences;
+pub mod price;
+pub mod price_lookup;
+pub mod rebalancing;
+pub mod saved;
+pub mod scenario;
+pub mod share;
+pub mod storage;
+pub mod tax;
+pub mod theme;
+pub mod ui;
+
+// Currently disabled until I build a backend to hide the
+// API key, that would get leaked in request headers.
+//
+// pub mod coinmarketcap;
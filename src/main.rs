@@ -1,14 +1,4 @@
-mod bitcoin;
-mod dollar;
-mod historical_data;
-mod numeric_input;
-mod price_lookup;
-mod ui;
-
-// Currently disabled until I build a backend to hide the
-// API key, that would get leaked in request headers.
-//
-// mod coinmarketcap;
+use whatif::ui;
 
 #[cfg(target_arch = "wasm32")]
 pub fn main() -> iced::Result {
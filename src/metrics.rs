@@ -0,0 +1,301 @@
+//! Return and risk of a scenario, from the value of the portfolio on each day
+//! of the holding period. Daily values use the mid price, without sell fees.
+use chrono::NaiveDate;
+
+use crate::{
+    bitcoin::MilliSatAmount,
+    dollar::DollarAmount,
+    price::Rounding,
+    price_lookup::PriceHistory,
+    scenario::{Acquisition, Error, Scenario, ScenarioResult},
+};
+
+/// Bitcoin trades every day of the year
+const DAYS_PER_YEAR: f64 = 365.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DailyValue {
+    pub date: NaiveDate,
+    pub value: DollarAmount,
+    /// Everything put in so far, bitcoin and cash
+    pub invested: DollarAmount,
+    /// Put in on this day, which doesn't count as a return
+    pub contribution: DollarAmount,
+}
+
+/// Portfolio value on each day there is a price for, from the buy date to the
+/// evaluation date, as purchases add up.
+pub fn daily_values(
+    scenario: &Scenario,
+    acquisition: &Acquisition,
+    prices: &impl PriceHistory,
+) -> Result<Vec<DailyValue>, Error> {
+    let mut purchases = acquisition.purchases.iter().peekable();
+    let mut bitcoin_msats = MilliSatAmount::from_msats(0);
+    let mut cash = DollarAmount::default();
+    let mut invested = DollarAmount::default();
+    let mut values = vec![];
+
+    for (date, quote) in prices.series(scenario.buy_date, scenario.evaluation_date) {
+        let mut contribution = DollarAmount::default();
+        while let Some(purchase) = purchases.next_if(|purchase| purchase.date <= date) {
+            bitcoin_msats = bitcoin_msats
+                .checked_add(purchase.bitcoin_msats)
+                .ok_or(Error::Overflow)?;
+            cash = scenario
+                .amount
+                .checked_sub(purchase.amount)
+                .and_then(|kept| cash.checked_add(kept))
+                .ok_or(Error::Overflow)?;
+            contribution = contribution
+                .checked_add(scenario.amount)
+                .ok_or(Error::Overflow)?;
+        }
+        invested = invested.checked_add(contribution).ok_or(Error::Overflow)?;
+
+        let value = quote
+            .price
+            .to_fiat(bitcoin_msats.to_sats(Rounding::Down))
+            .and_then(|bitcoin_value| bitcoin_value.checked_add(cash))
+            .ok_or(Error::Overflow)?;
+        values.push(DailyValue {
+            date,
+            value,
+            invested,
+            contribution,
+        });
+    }
+
+    Ok(values)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drawdown {
+    pub peak_date: NaiveDate,
+    pub trough_date: NaiveDate,
+    /// Loss from the peak, e.g. 0.5 if half the value was lost
+    pub loss: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    /// `value / invested - 1`
+    pub total_return: f64,
+    /// Compound annual growth rate, as if everything was invested on the buy
+    /// date
+    pub cagr: Option<f64>,
+    /// Standard deviation of daily returns, annualised
+    pub volatility: Option<f64>,
+    pub max_drawdown: Option<Drawdown>,
+    /// Annualised return above the risk-free rate, per unit of volatility
+    pub sharpe_ratio: Option<f64>,
+    pub best_day: Option<(NaiveDate, f64)>,
+    pub worst_day: Option<(NaiveDate, f64)>,
+}
+
+impl Metrics {
+    /// Daily returns leave out contributions, so dollar-cost averaging doesn't
+    /// look like the portfolio grew every time money was put in. They're only
+    /// taken between consecutive days: the move across a gap in the history,
+    /// e.g. from the end of the bundled prices to today's, isn't a day's.
+    /// `values` are the `daily_values` of the result.
+    pub fn compute(
+        scenario: &Scenario,
+        result: &ScenarioResult,
+        values: &[DailyValue],
+        risk_free_bps: u64,
+    ) -> Metrics {
+        let returns: Vec<(NaiveDate, f64)> = values
+            .windows(2)
+            .filter_map(|window| {
+                let (previous, today) = (window[0], window[1]);
+                if today.date.pred_opt() != Some(previous.date) {
+                    return None;
+                }
+                let grown = today.value.signed_sub(today.contribution)?.cents() as f64;
+                let previous = previous.value.cents() as f64;
+                (previous > 0.0).then(|| (today.date, grown / previous - 1.0))
+            })
+            .collect();
+
+        let years =
+            (scenario.evaluation_date - scenario.buy_date).num_days() as f64 / DAYS_PER_YEAR;
+        let cagr = (years > 0.0).then(|| result.multiple.powf(1.0 / years) - 1.0);
+
+        let count = returns.len() as f64;
+        let mean = returns.iter().map(|(_, r)| r).sum::<f64>() / count;
+        let volatility = (returns.len() > 1).then(|| {
+            let variance =
+                returns.iter().map(|(_, r)| (r - mean).powi(2)).sum::<f64>() / (count - 1.0);
+            (variance * DAYS_PER_YEAR).sqrt()
+        });
+        let risk_free = risk_free_bps as f64 / 10_000.0;
+        let sharpe_ratio = volatility
+            .filter(|volatility| *volatility > 0.0)
+            .map(|volatility| (mean * DAYS_PER_YEAR - risk_free) / volatility);
+
+        let by_return = |a: &&(NaiveDate, f64), b: &&(NaiveDate, f64)| a.1.total_cmp(&b.1);
+
+        Metrics {
+            total_return: result.multiple - 1.0,
+            cagr,
+            volatility,
+            max_drawdown: max_drawdown(values.first().map(|value| value.date), &returns),
+            sharpe_ratio,
+            best_day: returns.iter().max_by(by_return).copied(),
+            worst_day: returns.iter().min_by(by_return).copied(),
+        }
+    }
+}
+
+/// Largest fall of the cumulated returns from a previous high
+fn max_drawdown(start: Option<NaiveDate>, returns: &[(NaiveDate, f64)]) -> Option<Drawdown> {
+    let mut index = 1.0;
+    let mut peak = (start?, 1.0);
+    let mut max_drawdown: Option<Drawdown> = None;
+
+    for &(date, daily_return) in returns {
+        index *= 1.0 + daily_return;
+        if index > peak.1 {
+            peak = (date, index);
+        }
+        let loss = 1.0 - index / peak.1;
+        if loss > max_drawdown.map_or(0.0, |drawdown| drawdown.loss) {
+            max_drawdown = Some(Drawdown {
+                peak_date: peak.0,
+                trough_date: date,
+                loss,
+            });
+        }
+    }
+
+    max_drawdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fees::FeeModel,
+        price_lookup::test_prices::{date, history},
+        scenario::{Allocation, Frequency, Strategy},
+    };
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "{actual} isn't close to {expected}"
+        );
+    }
+
+    #[test]
+    fn test_metrics() {
+        let prices = history(&[
+            (date(2020, 1, 1), 100),
+            (date(2020, 1, 2), 200),
+            (date(2020, 1, 3), 100),
+            (date(2020, 1, 4), 150),
+        ]);
+        let scenario = Scenario {
+            amount: DollarAmount::from(1_000),
+            allocation: Allocation::default(),
+            buy_date: date(2020, 1, 1),
+            evaluation_date: date(2020, 1, 4),
+            strategy: Strategy::LumpSum,
+            fees: FeeModel::default(),
+        };
+        let result = scenario.evaluate(&prices).unwrap();
+        let values = daily_values(&scenario, &result.acquisition, &prices).unwrap();
+        let metrics = Metrics::compute(&scenario, &result, &values, 0);
+
+        assert_close(metrics.total_return, 0.5);
+        assert_eq!(metrics.best_day, Some((date(2020, 1, 2), 1.0)));
+        assert_eq!(metrics.worst_day, Some((date(2020, 1, 3), -0.5)));
+        assert_eq!(
+            metrics.max_drawdown,
+            Some(Drawdown {
+                peak_date: date(2020, 1, 2),
+                trough_date: date(2020, 1, 3),
+                loss: 0.5,
+            })
+        );
+        // Daily returns of +100%, -50% and +50%
+        assert_close(metrics.volatility.unwrap(), 0.76376 * 365f64.sqrt());
+        assert_close(
+            metrics.sharpe_ratio.unwrap(),
+            365.0 / 3.0 / (0.76376 * 365f64.sqrt()),
+        );
+        assert!(metrics.cagr.unwrap() > 1_000.0);
+    }
+
+    #[test]
+    fn test_contributions_are_not_returns() {
+        let prices = history(&[
+            (date(2020, 1, 1), 100),
+            (date(2020, 1, 2), 100),
+            (date(2020, 1, 3), 100),
+        ]);
+        let scenario = Scenario {
+            amount: DollarAmount::from(1_000),
+            allocation: Allocation::Percent(50),
+            buy_date: date(2020, 1, 1),
+            evaluation_date: date(2020, 1, 3),
+            strategy: Strategy::DollarCostAveraging(Frequency::Daily),
+            fees: FeeModel::default(),
+        };
+        let result = scenario.evaluate(&prices).unwrap();
+
+        let values = daily_values(&scenario, &result.acquisition, &prices).unwrap();
+        let amounts: Vec<(DollarAmount, DollarAmount)> = values
+            .iter()
+            .map(|value| (value.value, value.invested))
+            .collect();
+        assert_eq!(
+            amounts,
+            [(1_000, 1_000), (2_000, 2_000), (3_000, 3_000)]
+                .map(|(value, invested)| (DollarAmount::from(value), DollarAmount::from(invested)))
+        );
+
+        let metrics = Metrics::compute(&scenario, &result, &values, 400);
+        assert_eq!(
+            metrics.best_day.map(|(_, day_return)| day_return),
+            Some(0.0)
+        );
+        assert_eq!(metrics.max_drawdown, None);
+        assert_eq!(metrics.volatility, Some(0.0));
+        assert_eq!(metrics.sharpe_ratio, None);
+    }
+
+    #[test]
+    fn test_gaps_are_not_days() {
+        // Years without prices, as between the bundled history and today
+        let prices = history(&[
+            (date(2020, 1, 1), 100),
+            (date(2020, 1, 2), 110),
+            (date(2020, 1, 3), 99),
+            (date(2024, 1, 1), 1_000),
+            (date(2024, 1, 2), 900),
+        ]);
+        let scenario = Scenario {
+            amount: DollarAmount::from(1_000),
+            allocation: Allocation::default(),
+            buy_date: date(2020, 1, 1),
+            evaluation_date: date(2024, 1, 2),
+            strategy: Strategy::LumpSum,
+            fees: FeeModel::default(),
+        };
+        let result = scenario.evaluate(&prices).unwrap();
+        let values = daily_values(&scenario, &result.acquisition, &prices).unwrap();
+        let metrics = Metrics::compute(&scenario, &result, &values, 0);
+
+        assert_close(metrics.total_return, 8.0);
+        assert_eq!(
+            metrics.best_day.map(|(date, _)| date),
+            Some(date(2020, 1, 2))
+        );
+        assert_close(metrics.best_day.unwrap().1, 0.1);
+        assert_close(metrics.worst_day.unwrap().1, -0.1);
+        assert!(metrics.volatility.unwrap() < 0.2 * 365f64.sqrt());
+        assert_close(metrics.max_drawdown.unwrap().loss, 0.19);
+    }
+}
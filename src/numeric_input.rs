@@ -1,15 +1,23 @@
 //! Numeric input field (like an HTML <input type="number" />) for `iced`, with
-//! configurable - and + buttons.
+//! configurable - and + buttons. The arrow keys step the value too, and page
+//! up and down step it ten times faster.
 
 use iced::{
     alignment::{self, Alignment},
+    keyboard::{key::Named, Key},
     widget::{button, component, row, text, text_input, Component},
     Element, Length, Size,
 };
 
+use crate::key_bindings::key_bindings;
+
+/// Steps per press of page up or page down
+const PAGE_STEPS: u64 = 10;
+
 pub struct NumericInput<Message> {
     value: Option<u64>,
     step: u64,
+    placeholder: String,
     on_change: Box<dyn Fn(Option<u64>) -> Message>,
 }
 
@@ -24,8 +32,8 @@ pub fn numeric_input<Message>(
 #[derive(Debug, Clone)]
 pub enum Event {
     InputChanged(String),
-    IncrementPressed,
-    DecrementPressed,
+    /// Up by a number of steps, or down if negative
+    Stepped(i64),
 }
 
 impl<Message> NumericInput<Message> {
@@ -37,9 +45,16 @@ impl<Message> NumericInput<Message> {
         Self {
             value,
             step,
+            placeholder: String::from("Type a number"),
             on_change: Box::new(on_change),
         }
     }
+
+    /// Shown while the field is empty
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
 }
 
 impl<Message> Component<Message> for NumericInput<Message> {
@@ -48,12 +63,14 @@ impl<Message> Component<Message> for NumericInput<Message> {
 
     fn update(&mut self, _state: &mut Self::State, event: Event) -> Option<Message> {
         match event {
-            Event::IncrementPressed => Some((self.on_change)(Some(
-                self.value.unwrap_or_default().saturating_add(self.step),
-            ))),
-            Event::DecrementPressed => Some((self.on_change)(Some(
-                self.value.unwrap_or_default().saturating_sub(self.step),
-            ))),
+            Event::Stepped(steps) => {
+                let change = self.step.saturating_mul(steps.unsigned_abs());
+                let value = self.value.unwrap_or_default();
+                Some((self.on_change)(Some(match steps < 0 {
+                    true => value.saturating_sub(change),
+                    false => value.saturating_add(change),
+                })))
+            }
             Event::InputChanged(value) => {
                 if value.is_empty() {
                     Some((self.on_change)(None))
@@ -64,7 +81,7 @@ impl<Message> Component<Message> for NumericInput<Message> {
         }
     }
 
-    fn view(&self, _state: &Self::State) -> Element<Event> {
+    fn view(&self, _state: &Self::State) -> Element<'_, Event> {
         let button = |label, on_press| {
             button(
                 text(label)
@@ -79,18 +96,27 @@ impl<Message> Component<Message> for NumericInput<Message> {
         };
 
         row![
-            button("-", Event::DecrementPressed),
-            text_input(
-                "Type a number",
-                self.value
-                    .as_ref()
-                    .map(u64::to_string)
-                    .as_deref()
-                    .unwrap_or(""),
-            )
-            .on_input(Event::InputChanged)
-            .padding(10),
-            button("+", Event::IncrementPressed),
+            button("-", Event::Stepped(-1)),
+            key_bindings(
+                text_input(
+                    &self.placeholder,
+                    self.value
+                        .as_ref()
+                        .map(u64::to_string)
+                        .as_deref()
+                        .unwrap_or(""),
+                )
+                .on_input(Event::InputChanged)
+                .padding(10),
+                |key, _| match key {
+                    Key::Named(Named::ArrowUp) => Some(Event::Stepped(1)),
+                    Key::Named(Named::ArrowDown) => Some(Event::Stepped(-1)),
+                    Key::Named(Named::PageUp) => Some(Event::Stepped(PAGE_STEPS as i64)),
+                    Key::Named(Named::PageDown) => Some(Event::Stepped(-(PAGE_STEPS as i64))),
+                    _ => None,
+                },
+            ),
+            button("+", Event::Stepped(1)),
         ]
         .align_items(Alignment::Center)
         .spacing(10)
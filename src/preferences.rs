@@ -0,0 +1,56 @@
+//! Choices about the app itself, rather than the scenario, kept between
+//! sessions.
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::Language, storage, theme::ThemeMode};
+
+/// Stored as `preferences.json`, or under `whatif.preferences`
+const PREFERENCES: &str = "preferences";
+
+/// Anything missing, e.g. added since they were stored, is the default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub theme: ThemeMode,
+    /// `None` to follow the system or browser
+    pub language: Option<Language>,
+}
+
+impl Preferences {
+    pub fn language(&self) -> Language {
+        self.language.unwrap_or_else(Language::detect)
+    }
+
+    pub fn load() -> Result<Self, storage::Error> {
+        storage::load(PREFERENCES).map(Option::unwrap_or_default)
+    }
+
+    pub fn store(&self) -> Result<(), storage::Error> {
+        storage::store(PREFERENCES, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{from_json, to_json};
+
+    #[test]
+    fn test_json() {
+        let preferences = Preferences {
+            theme: ThemeMode::Light,
+            language: Some(Language::French),
+        };
+        let json = to_json(&preferences).unwrap();
+        assert_eq!(
+            json,
+            "{\n  \"theme\": \"Light\",\n  \"language\": \"French\"\n}"
+        );
+        assert_eq!(from_json::<Preferences>(&json).unwrap(), preferences);
+
+        assert_eq!(
+            from_json::<Preferences>("{}").unwrap(),
+            Preferences::default()
+        );
+    }
+}
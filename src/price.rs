@@ -0,0 +1,197 @@
+//! Bitcoin price in fiat, to convert amounts between the two.
+//!
+//! Serialized like the fiat amount one bitcoin is worth:
+//! `{"amount": "66999.70", "currency": "USD"}`
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bitcoin::{BitcoinAmount, MilliSatAmount},
+    dollar::DollarAmount,
+};
+
+/// How to round a conversion that doesn't fall on a whole sat or cent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Nearest,
+    Up,
+}
+
+/// Fiat price of one bitcoin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Price {
+    per_btc: DollarAmount,
+}
+
+impl Price {
+    pub fn per_btc(per_btc: DollarAmount) -> Self {
+        Self { per_btc }
+    }
+
+    /// Average price paid when `fiat` bought `btc`. `None` if `btc` is zero.
+    pub fn from_cost(fiat: DollarAmount, btc: BitcoinAmount) -> Option<Self> {
+        mul_div(
+            fiat.cents(),
+            BitcoinAmount::one_btc().sats(),
+            btc.sats(),
+            Rounding::Nearest,
+        )
+        .map(|cents| Price::per_btc(DollarAmount::from_cents(cents)))
+    }
+
+    pub fn fiat_per_btc(self) -> DollarAmount {
+        self.per_btc
+    }
+
+    /// How much `btc` is worth, to the nearest cent.
+    pub fn to_fiat(self, btc: BitcoinAmount) -> Option<DollarAmount> {
+        self.to_fiat_rounded(btc, Rounding::Nearest)
+    }
+
+    pub fn to_fiat_rounded(self, btc: BitcoinAmount, rounding: Rounding) -> Option<DollarAmount> {
+        mul_div(
+            btc.sats(),
+            self.per_btc.cents(),
+            BitcoinAmount::one_btc().sats(),
+            rounding,
+        )
+        .map(DollarAmount::from_cents)
+    }
+
+    /// How much bitcoin `fiat` buys. Rounded down, since you can't buy a
+    /// fraction of a sat. `None` if the price is zero.
+    pub fn to_btc(self, fiat: DollarAmount) -> Option<BitcoinAmount> {
+        self.to_btc_rounded(fiat, Rounding::Down)
+    }
+
+    /// Same as `to_btc`, to the msat.
+    pub fn to_msats(self, fiat: DollarAmount, rounding: Rounding) -> Option<MilliSatAmount> {
+        let msats_in_btc = BitcoinAmount::one_btc().to_msats()?.msats();
+        mul_div(fiat.cents(), msats_in_btc, self.per_btc.cents(), rounding)
+            .map(MilliSatAmount::from_msats)
+    }
+
+    pub fn to_btc_rounded(self, fiat: DollarAmount, rounding: Rounding) -> Option<BitcoinAmount> {
+        mul_div(
+            fiat.cents(),
+            BitcoinAmount::one_btc().sats(),
+            self.per_btc.cents(),
+            rounding,
+        )
+        .map(BitcoinAmount::from)
+    }
+}
+
+impl Display for Price {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/BTC", self.per_btc)
+    }
+}
+
+/// `value * multiplier / divisor`, through a u128 so the multiplication can't
+/// overflow. `None` if dividing by zero or the result doesn't fit in a u64.
+pub fn mul_div(value: u64, multiplier: u64, divisor: u64, rounding: Rounding) -> Option<u64> {
+    if divisor == 0 {
+        return None;
+    }
+
+    let product = value as u128 * multiplier as u128;
+    let divisor = divisor as u128;
+    let (quotient, remainder) = (product / divisor, product % divisor);
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Nearest => remainder * 2 >= divisor,
+        Rounding::Up => remainder > 0,
+    };
+
+    u64::try_from(quotient + round_up as u128).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_div() {
+        assert_eq!(mul_div(10, 2, 3, Rounding::Down), Some(6));
+        assert_eq!(mul_div(10, 2, 3, Rounding::Nearest), Some(7));
+        assert_eq!(mul_div(10, 2, 3, Rounding::Up), Some(7));
+        assert_eq!(mul_div(10, 1, 4, Rounding::Nearest), Some(3));
+        assert_eq!(mul_div(9, 1, 4, Rounding::Nearest), Some(2));
+        assert_eq!(mul_div(9, 1, 3, Rounding::Up), Some(3));
+        assert_eq!(mul_div(1, 1, 0, Rounding::Down), None);
+
+        // Would overflow a u64 before dividing
+        assert_eq!(
+            mul_div(u64::MAX, 100, 1_000, Rounding::Down),
+            Some(u64::MAX / 10)
+        );
+        assert_eq!(mul_div(u64::MAX, 2, 1, Rounding::Down), None);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let price = Price::per_btc(DollarAmount::from(60_000));
+
+        assert_eq!(
+            price.to_btc(DollarAmount::from(10_000)),
+            Some(BitcoinAmount::from(16_666_666))
+        );
+        assert_eq!(
+            price.to_btc_rounded(DollarAmount::from(10_000), Rounding::Nearest),
+            Some(BitcoinAmount::from(16_666_667))
+        );
+        assert_eq!(
+            price.to_fiat(BitcoinAmount::from(16_666_666)),
+            Some(DollarAmount::from(10_000))
+        );
+        assert_eq!(
+            price.to_fiat(BitcoinAmount::one_btc()),
+            Some(DollarAmount::from(60_000))
+        );
+
+        // Early prices were under a dollar
+        let price = Price::per_btc(DollarAmount::from(0.1));
+        assert_eq!(
+            price.to_btc(DollarAmount::from(1)),
+            Some(BitcoinAmount::from(1_000_000_000))
+        );
+
+        let price = Price::per_btc(DollarAmount::from(60_000));
+        assert_eq!(
+            price.to_msats(DollarAmount::from(1), Rounding::Down),
+            Some(MilliSatAmount::from_msats(1_666_666))
+        );
+        assert_eq!(
+            price.to_btc(DollarAmount::from_cents(1)),
+            Some(BitcoinAmount::from(16))
+        );
+        assert_eq!(
+            price.to_msats(DollarAmount::from_cents(1), Rounding::Nearest),
+            Some(MilliSatAmount::from_msats(16_667))
+        );
+
+        assert_eq!(
+            Price::from_cost(DollarAmount::from(10_000), BitcoinAmount::from(16_666_666)),
+            Some(Price::per_btc(DollarAmount::from(60_000)))
+        );
+        assert_eq!(
+            Price::from_cost(DollarAmount::from(1), BitcoinAmount::default()),
+            None
+        );
+
+        let free = Price::per_btc(DollarAmount::from(0));
+        assert_eq!(free.to_btc(DollarAmount::from(1)), None);
+    }
+
+    #[test]
+    fn test_wire_format() {
+        let price = Price::per_btc(DollarAmount::from(66_999.7));
+        let json = r#"{"amount":"66999.70","currency":"USD"}"#;
+        assert_eq!(serde_json::to_string(&price).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Price>(json).unwrap(), price);
+    }
+}
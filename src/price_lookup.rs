@@ -3,6 +3,7 @@
 //! coindesk.com API. Anything in between will be missing.
 use std::{
     collections::HashMap,
+    ops::RangeInclusive,
     sync::{
         mpsc::{self, Receiver, Sender},
         Arc, RwLock,
@@ -12,23 +13,129 @@ use std::{
 use chrono::{NaiveDate, Utc};
 use serde::Deserialize;
 
-use crate::{bitcoin::BitcoinAmount, dollar::DollarAmount, historical_data::get_prices_from_csv};
+use crate::{dollar::DollarAmount, historical_data::get_prices_from_csv, price::Price};
 
 // Makes it work more easily on WASM + other platforms
-const PRICE_HISTORY: &'static [u8] = include_bytes!("../data/price_history.csv");
+const PRICE_HISTORY: &[u8] = include_bytes!("../data/price_history.csv");
 const COINDESK_LATEST_BTC_PRICE: &str = "https://api.coindesk.com/v1/bpi/currentprice/USD.json";
 
 /// Loads Bitcoin prices from different sources so we can look them up
 pub struct PriceDatabase {
-    pub data: Arc<RwLock<HashMap<NaiveDate, (DollarAmount, BitcoinAmount)>>>,
+    pub data: Arc<RwLock<HashMap<NaiveDate, Quote>>>,
+    /// Prices being fetched, or that couldn't be
+    fetches: Arc<RwLock<HashMap<NaiveDate, Fetch>>>,
     updates_sender: Sender<NaiveDate>,
 }
 
+/// Where a price comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// Bundled CSV file
+    History,
+    /// Live price from the CoinDesk API
+    CoinDesk,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::History => write!(f, "historical data"),
+            Source::CoinDesk => write!(f, "CoinDesk"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quote {
+    pub price: Price,
+    pub source: Source,
+}
+
+/// Read-only access to prices that are already known, without fetching
+/// anything, so calculations don't have side effects.
+pub trait PriceHistory {
+    fn quote(&self, date: NaiveDate) -> Option<Quote>;
+
+    /// Every known quote from `from` to `to` included, skipping missing days.
+    fn series(&self, from: NaiveDate, to: NaiveDate) -> Vec<(NaiveDate, Quote)> {
+        from.iter_days()
+            .take_while(|date| *date <= to)
+            .filter_map(|date| self.quote(date).map(|quote| (date, quote)))
+            .collect()
+    }
+}
+
+impl PriceHistory for HashMap<NaiveDate, Quote> {
+    fn quote(&self, date: NaiveDate) -> Option<Quote> {
+        self.get(&date).copied()
+    }
+}
+
+impl PriceHistory for PriceDatabase {
+    fn quote(&self, date: NaiveDate) -> Option<Quote> {
+        self.data.read().ok()?.quote(date)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Fetch {
+    Pending,
+    Failed(String),
+}
+
+/// Why there's no price for a date, and none will be fetched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unavailable {
+    Future,
+    /// Before the first date of the history
+    BeforeHistory(NaiveDate),
+    /// Past dates missing from the history can't be fetched
+    Missing,
+    /// Today's price isn't fetched on the web yet, so retrying won't help
+    NotFetchedOnWeb,
+}
+
+impl std::fmt::Display for Unavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unavailable::Future => write!(f, "that's in the future"),
+            Unavailable::BeforeHistory(first) => {
+                write!(f, "the price history starts on {first}")
+            }
+            Unavailable::Missing => write!(
+                f,
+                "it's missing from the price history, and only today's price can be fetched"
+            ),
+            Unavailable::NotFetchedOnWeb => {
+                write!(f, "live prices aren't fetched on the web yet")
+            }
+        }
+    }
+}
+
+/// Where the lookup of a price is at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lookup {
+    Loading,
+    Loaded(Quote),
+    Unavailable(Unavailable),
+    /// Fetching failed, and can be retried
+    Failed(String),
+}
+
 #[derive(Debug)]
 pub enum Error {
     GetPricesFromCsv(csv::Error),
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::GetPricesFromCsv(err) => write!(f, "reading prices from CSV: {err}"),
+        }
+    }
+}
+
 // Price in db: return it
 // Not in DB: just fetch it and cache it!
 
@@ -48,10 +155,21 @@ impl PriceDatabase {
             "Loaded conversion table with {} records.",
             conversion_table.len()
         );
+        let conversion_table = conversion_table
+            .into_iter()
+            .map(|(date, price)| {
+                let quote = Quote {
+                    price,
+                    source: Source::History,
+                };
+                (date, quote)
+            })
+            .collect();
         let conversion_table = Arc::new(RwLock::new(conversion_table));
         let (tx, rx): (Sender<NaiveDate>, Receiver<NaiveDate>) = mpsc::channel();
         let db = Self {
             data: conversion_table,
+            fetches: Arc::default(),
             updates_sender: tx,
         };
 
@@ -62,16 +180,54 @@ impl PriceDatabase {
         Ok((db, rx))
     }
 
-    pub fn get(&self, date: NaiveDate) -> Option<(DollarAmount, BitcoinAmount)> {
+    /// First and last dates there's a price for
+    pub fn dates(&self) -> Option<RangeInclusive<NaiveDate>> {
+        let data = self.data.read().ok()?;
+        Some(*data.keys().min()?..=*data.keys().max()?)
+    }
+
+    /// Where the lookup of the price for a date is at, without fetching it.
+    pub fn lookup(&self, date: NaiveDate) -> Lookup {
+        if let Some(quote) = self.quote(date) {
+            return Lookup::Loaded(quote);
+        }
+        let fetch = self
+            .fetches
+            .read()
+            .ok()
+            .and_then(|fetches| fetches.get(&date).cloned());
+        match fetch {
+            Some(Fetch::Pending) => Lookup::Loading,
+            Some(Fetch::Failed(err)) => Lookup::Failed(err),
+            None if date > Utc::now().date_naive() => Lookup::Unavailable(Unavailable::Future),
+            None if cfg!(target_arch = "wasm32") && date == Utc::now().date_naive() => {
+                Lookup::Unavailable(Unavailable::NotFetchedOnWeb)
+            }
+            None => match self.dates() {
+                Some(dates) if date < *dates.start() => {
+                    Lookup::Unavailable(Unavailable::BeforeHistory(*dates.start()))
+                }
+                _ => Lookup::Unavailable(Unavailable::Missing),
+            },
+        }
+    }
+
+    /// Fetches a price again after it failed.
+    pub fn retry(&self, date: NaiveDate) -> Option<Quote> {
+        if let Ok(mut fetches) = self.fetches.write() {
+            fetches.remove(&date);
+        }
+        self.get(date)
+    }
+
+    /// Looks up the price for a date, fetching it in the background if it's
+    /// missing. Use `PriceHistory::quote` to look up without fetching.
+    pub fn get(&self, date: NaiveDate) -> Option<Quote> {
         println!("Received a price request for {date:?}");
         // Only get today's price from the API. A backend is needed
         // for more prices.
 
-        if let Ok(Some(quote)) = self
-            .data
-            .read()
-            .and_then(|data| Ok(data.get(&date).cloned()))
-        {
+        if let Some(quote) = self.quote(date) {
             println!("We already have the price for this date!");
             return Some(quote);
         }
@@ -82,9 +238,33 @@ impl PriceDatabase {
             return None;
         }
 
+        if cfg!(target_arch = "wasm32") {
+            // `lookup` says why, rather than a failure that can be retried
+            return None;
+        }
+
+        match self.fetches.write() {
+            // Already fetching it, or it failed until retried
+            Ok(fetches) if fetches.contains_key(&date) => return None,
+            Ok(mut fetches) => {
+                fetches.insert(date, Fetch::Pending);
+            }
+            Err(err) => println!("Price fetches mutex is poisoned: {err}"),
+        }
+
         {
             let database = Arc::clone(&self.data);
+            let fetches = Arc::clone(&self.fetches);
             let tx = self.updates_sender.clone();
+            // Forgets the fetch once it succeeded, or keeps why it failed
+            let finish = move |failure: Option<String>| {
+                if let Ok(mut fetches) = fetches.write() {
+                    match failure {
+                        Some(err) => fetches.insert(date, Fetch::Failed(err)),
+                        None => fetches.remove(&date),
+                    };
+                }
+            };
 
             let fut = async move {
                 // We don't have today's price, let's fetch it!
@@ -92,6 +272,8 @@ impl PriceDatabase {
                     Ok(resp) => resp,
                     Err(err) => {
                         println!("Fetching current BTC/USD quote: {err}");
+                        finish(Some(err.to_string()));
+                        let _ = tx.send(date);
                         return;
                     }
                 };
@@ -100,28 +282,34 @@ impl PriceDatabase {
                     Ok(resp) => resp,
                     Err(err) => {
                         println!("Error parsing current BTC/USD response, good luck debugging the spaghetti code. {err}");
+                        finish(Some(format!("unexpected response from CoinDesk: {err}")));
+                        let _ = tx.send(date);
                         return;
                     }
                 };
 
-                let dollar_price = DollarAmount::from(resp.bpi.usd.rate_float);
-                let bitcoin_price = BitcoinAmount::one_btc();
+                let price = Price::per_btc(DollarAmount::from(resp.bpi.usd.rate_float));
 
                 match database.write() {
                     Ok(mut data) => {
-                        data.insert(date, (dollar_price, bitcoin_price));
+                        let quote = Quote {
+                            price,
+                            source: Source::CoinDesk,
+                        };
+                        data.insert(date, quote);
+                        finish(None);
                         if let Err(err) = tx.send(date) {
                             println!("Send update upstream: {err}")
                         }
                     }
                     Err(err) => {
                         println!("Price database mutex is poisoned: {err}");
+                        finish(Some(String::from("the price database is unavailable")));
+                        let _ = tx.send(date);
                     }
                 };
             };
 
-            // #[cfg(target_arch = "wasm32")]
-            // iced::futures::executor::block_on(fut);
             #[cfg(not(target_arch = "wasm32"))]
             tokio::spawn(fut);
         }
@@ -138,10 +326,78 @@ struct CoinDeskBitcoinResponse {
 #[derive(Deserialize)]
 struct Bpi {
     #[serde(rename = "USD")]
-    usd: Quote,
+    usd: CoinDeskQuote,
 }
 
 #[derive(Deserialize)]
-struct Quote {
+struct CoinDeskQuote {
     rate_float: f64,
 }
+
+/// Small price histories to test calculations with
+#[cfg(test)]
+pub(crate) mod test_prices {
+    use std::collections::HashMap;
+
+    use chrono::NaiveDate;
+
+    use super::{Quote, Source};
+    use crate::{dollar::DollarAmount, price::Price};
+
+    pub fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    pub fn history(prices: &[(NaiveDate, u32)]) -> HashMap<NaiveDate, Quote> {
+        prices
+            .iter()
+            .map(|&(date, dollars)| {
+                let quote = Quote {
+                    price: Price::per_btc(DollarAmount::from(dollars)),
+                    source: Source::History,
+                };
+                (date, quote)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{test_prices::*, *};
+
+    #[test]
+    fn test_lookup() {
+        let database = PriceDatabase {
+            data: Arc::new(RwLock::new(history(&[(date(2010, 7, 18), 1)]))),
+            fetches: Arc::new(RwLock::new(HashMap::from([
+                (date(2020, 1, 1), Fetch::Pending),
+                (date(2021, 1, 1), Fetch::Failed(String::from("timed out"))),
+            ]))),
+            updates_sender: mpsc::channel().0,
+        };
+
+        assert!(matches!(
+            database.lookup(date(2010, 7, 18)),
+            Lookup::Loaded(_)
+        ));
+        assert_eq!(database.lookup(date(2020, 1, 1)), Lookup::Loading);
+        assert_eq!(
+            database.lookup(date(2021, 1, 1)),
+            Lookup::Failed(String::from("timed out"))
+        );
+        assert_eq!(
+            database.lookup(date(2009, 1, 3)),
+            Lookup::Unavailable(Unavailable::BeforeHistory(date(2010, 7, 18)))
+        );
+        assert_eq!(
+            database.lookup(date(2015, 1, 1)),
+            Lookup::Unavailable(Unavailable::Missing)
+        );
+        let tomorrow = Utc::now().date_naive().succ_opt().unwrap();
+        assert_eq!(
+            database.lookup(tomorrow),
+            Lookup::Unavailable(Unavailable::Future)
+        );
+    }
+}
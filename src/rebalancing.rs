@@ -0,0 +1,263 @@
+//! Simulation of a bitcoin/cash portfolio that is rebalanced to keep a target
+//! weight of bitcoin, compared to buying and holding.
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::{
+    bitcoin::BitcoinAmount,
+    dollar::DollarAmount,
+    price::{mul_div, Price, Rounding},
+    price_lookup::PriceHistory,
+    scenario::Error,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Monthly,
+    Quarterly,
+    Yearly,
+}
+
+impl Period {
+    /// Identifies the period a date is in, to know when a new one starts
+    fn of(self, date: NaiveDate) -> (i32, u32) {
+        match self {
+            Period::Monthly => (date.year(), date.month0()),
+            Period::Quarterly => (date.year(), date.month0() / 3),
+            Period::Yearly => (date.year(), 0),
+        }
+    }
+}
+
+/// When to bring the portfolio back to its target weight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebalanceRule {
+    /// Buy and hold
+    Never,
+    /// On the first day of each period
+    Every(Period),
+    /// Whenever the bitcoin weight is this many percentage points away from
+    /// the target
+    Drift(u64),
+}
+
+impl RebalanceRule {
+    pub const ALL: [RebalanceRule; 6] = [
+        RebalanceRule::Never,
+        RebalanceRule::Every(Period::Monthly),
+        RebalanceRule::Every(Period::Quarterly),
+        RebalanceRule::Every(Period::Yearly),
+        RebalanceRule::Drift(5),
+        RebalanceRule::Drift(10),
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rebalancing {
+    pub amount: DollarAmount,
+    /// Target weight of bitcoin in the portfolio, from 0 to 100
+    pub bitcoin_percent: u64,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub rule: RebalanceRule,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    /// Portfolio value on each day there is a price for, ending on the end
+    /// date
+    pub values: Vec<(NaiveDate, DollarAmount)>,
+    pub rebalances: usize,
+}
+
+impl Simulation {
+    /// Value on the end date
+    pub fn final_value(&self) -> Option<DollarAmount> {
+        self.values.last().map(|(_, value)| *value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebalancingResult {
+    pub rebalanced: Simulation,
+    pub buy_and_hold: Simulation,
+}
+
+/// Portfolio holdings while simulating
+struct Holdings {
+    bitcoin: BitcoinAmount,
+    cash: DollarAmount,
+}
+
+impl Holdings {
+    /// Converts what's needed for bitcoin to be `bitcoin_percent` of `value`.
+    fn rebalance(value: DollarAmount, bitcoin_percent: u64, price: Price) -> Result<Self, Error> {
+        let target = mul_div(value.cents(), bitcoin_percent, 100, Rounding::Down)
+            .map(DollarAmount::from_cents)
+            .ok_or(Error::Overflow)?;
+        let bitcoin = price.to_btc(target).ok_or(Error::Overflow)?;
+        let cash = price
+            .to_fiat(bitcoin)
+            .and_then(|converted| value.checked_sub(converted))
+            .ok_or(Error::Overflow)?;
+
+        Ok(Self { bitcoin, cash })
+    }
+
+    fn values(&self, price: Price) -> Result<(DollarAmount, DollarAmount), Error> {
+        let bitcoin_value = price.to_fiat(self.bitcoin).ok_or(Error::Overflow)?;
+        let value = bitcoin_value
+            .checked_add(self.cash)
+            .ok_or(Error::Overflow)?;
+
+        Ok((bitcoin_value, value))
+    }
+}
+
+impl Rebalancing {
+    fn should_rebalance(
+        &self,
+        rule: RebalanceRule,
+        previous_date: NaiveDate,
+        date: NaiveDate,
+        bitcoin_value: DollarAmount,
+        value: DollarAmount,
+    ) -> bool {
+        match rule {
+            RebalanceRule::Never => false,
+            RebalanceRule::Every(period) => period.of(previous_date) != period.of(date),
+            RebalanceRule::Drift(points) => bitcoin_value.ratio(value).is_some_and(|weight| {
+                (weight * 100.0 - self.bitcoin_percent as f64).abs() >= points as f64
+            }),
+        }
+    }
+
+    fn simulate(
+        &self,
+        prices: &impl PriceHistory,
+        rule: RebalanceRule,
+    ) -> Result<Simulation, Error> {
+        if self.bitcoin_percent > 100 {
+            return Err(Error::InvalidAllocation);
+        }
+
+        let start = prices
+            .quote(self.start_date)
+            .ok_or(Error::MissingPrice(self.start_date))?;
+        // Both simulations are worth something on the end date, like the
+        // scenario, rather than on whichever day last had a price
+        if prices.quote(self.end_date).is_none() {
+            return Err(Error::MissingPrice(self.end_date));
+        }
+        let mut holdings = Holdings::rebalance(self.amount, self.bitcoin_percent, start.price)?;
+        let mut previous_date = self.start_date;
+        let mut simulation = Simulation {
+            values: vec![],
+            rebalances: 0,
+        };
+
+        for (date, quote) in prices.series(self.start_date, self.end_date) {
+            let (bitcoin_value, mut value) = holdings.values(quote.price)?;
+            if self.should_rebalance(rule, previous_date, date, bitcoin_value, value) {
+                holdings = Holdings::rebalance(value, self.bitcoin_percent, quote.price)?;
+                value = holdings.values(quote.price)?.1;
+                simulation.rebalances += 1;
+            }
+
+            simulation.values.push((date, value));
+            previous_date = date;
+        }
+
+        Ok(simulation)
+    }
+
+    pub fn run(&self, prices: &impl PriceHistory) -> Result<RebalancingResult, Error> {
+        if self.end_date < self.start_date {
+            return Err(Error::EvaluationBeforeBuy);
+        }
+
+        Ok(RebalancingResult {
+            rebalanced: self.simulate(prices, self.rule)?,
+            buy_and_hold: self.simulate(prices, RebalanceRule::Never)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price_lookup::test_prices::{date, history};
+
+    #[test]
+    fn test_rebalancing() {
+        // Bitcoin doubles, then halves back
+        let prices = history(&[
+            (date(2020, 1, 1), 10_000),
+            (date(2020, 1, 15), 20_000),
+            (date(2020, 2, 1), 20_000),
+            (date(2020, 3, 1), 10_000),
+        ]);
+        let mut rebalancing = Rebalancing {
+            amount: DollarAmount::from(1_000),
+            bitcoin_percent: 50,
+            start_date: date(2020, 1, 1),
+            end_date: date(2020, 3, 1),
+            rule: RebalanceRule::Every(Period::Monthly),
+        };
+
+        let result = rebalancing.run(&prices).unwrap();
+        let values: Vec<DollarAmount> = result.buy_and_hold.values.iter().map(|v| v.1).collect();
+        assert_eq!(values, [1_000, 1_500, 1_500, 1_000].map(DollarAmount::from));
+        assert_eq!(result.buy_and_hold.rebalances, 0);
+
+        // Rebalanced on 2020-02-01 at $1,500: $750 of bitcoin, $750 cash,
+        // then the bitcoin halves.
+        assert_eq!(result.rebalanced.rebalances, 2);
+        assert_eq!(
+            result.rebalanced.final_value(),
+            Some(DollarAmount::from(1_125))
+        );
+
+        rebalancing.rule = RebalanceRule::Drift(10);
+        let result = rebalancing.run(&prices).unwrap();
+        // 66% bitcoin on 2020-01-15, then 50% after rebalancing until the
+        // price halves: 33% on 2020-03-01
+        assert_eq!(result.rebalanced.rebalances, 2);
+        assert_eq!(
+            result.rebalanced.final_value(),
+            Some(DollarAmount::from(1_125))
+        );
+
+        rebalancing.rule = RebalanceRule::Every(Period::Yearly);
+        let result = rebalancing.run(&prices).unwrap();
+        assert_eq!(result.rebalanced, result.buy_and_hold);
+    }
+
+    #[test]
+    fn test_errors() {
+        let prices = history(&[(date(2020, 1, 1), 10_000)]);
+        let mut rebalancing = Rebalancing {
+            amount: DollarAmount::from(1_000),
+            bitcoin_percent: 101,
+            start_date: date(2020, 1, 1),
+            end_date: date(2020, 3, 1),
+            rule: RebalanceRule::Never,
+        };
+        assert_eq!(rebalancing.run(&prices), Err(Error::InvalidAllocation));
+
+        rebalancing.bitcoin_percent = 50;
+        assert_eq!(
+            rebalancing.run(&prices),
+            Err(Error::MissingPrice(date(2020, 3, 1)))
+        );
+
+        rebalancing.start_date = date(2019, 1, 1);
+        assert_eq!(
+            rebalancing.run(&prices),
+            Err(Error::MissingPrice(date(2019, 1, 1)))
+        );
+
+        rebalancing.start_date = date(2021, 1, 1);
+        assert_eq!(rebalancing.run(&prices), Err(Error::EvaluationBeforeBuy));
+    }
+}
@@ -0,0 +1,107 @@
+//! Named scenarios the user saved, to come back to them and compare them,
+//! kept between sessions.
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dollar::DollarAmount,
+    fees::FeeModel,
+    scenario::{Allocation, Scenario, Strategy},
+    storage,
+};
+
+/// Stored as `saved_scenarios.json`, or under `whatif.saved_scenarios`
+const SAVED_SCENARIOS: &str = "saved_scenarios";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedScenario {
+    pub name: String,
+    pub amount: DollarAmount,
+    pub allocation: Allocation,
+    pub buy_date: NaiveDate,
+    /// `None` to evaluate at today's price
+    pub evaluation_date: Option<NaiveDate>,
+    pub strategy: Strategy,
+    pub fees: FeeModel,
+}
+
+impl SavedScenario {
+    pub fn new(name: String, scenario: &Scenario, evaluation_date: Option<NaiveDate>) -> Self {
+        Self {
+            name,
+            amount: scenario.amount,
+            allocation: scenario.allocation,
+            buy_date: scenario.buy_date,
+            evaluation_date,
+            strategy: scenario.strategy,
+            fees: scenario.fees,
+        }
+    }
+
+    pub fn scenario(&self, today: NaiveDate) -> Scenario {
+        Scenario {
+            amount: self.amount,
+            allocation: self.allocation,
+            buy_date: self.buy_date,
+            evaluation_date: self.evaluation_date.unwrap_or(today),
+            strategy: self.strategy,
+            fees: self.fees,
+        }
+    }
+}
+
+/// Saved scenarios, or none if nothing was saved yet.
+pub fn load() -> Result<Vec<SavedScenario>, storage::Error> {
+    storage::load(SAVED_SCENARIOS).map(Option::unwrap_or_default)
+}
+
+/// Replaces everything that was saved.
+pub fn store(scenarios: &[SavedScenario]) -> Result<(), storage::Error> {
+    storage::store(SAVED_SCENARIOS, &scenarios)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        price_lookup::test_prices::date,
+        scenario::Frequency,
+        storage::{from_json, to_json, Error},
+    };
+
+    #[test]
+    fn test_json() {
+        let scenario = Scenario {
+            amount: DollarAmount::from(100),
+            allocation: Allocation::Percent(50),
+            buy_date: date(2015, 1, 1),
+            evaluation_date: date(2021, 11, 10),
+            strategy: Strategy::DollarCostAveraging(Frequency::Monthly),
+            fees: FeeModel {
+                percent_bps: 100,
+                ..FeeModel::default()
+            },
+        };
+        let saved = vec![
+            SavedScenario::new(String::from("$100 a month"), &scenario, None),
+            SavedScenario::new(
+                String::from("Until the top"),
+                &scenario,
+                Some(date(2021, 11, 10)),
+            ),
+        ];
+
+        let json = to_json(&saved).unwrap();
+        assert_eq!(from_json::<Vec<SavedScenario>>(&json).unwrap(), saved);
+        assert_eq!(
+            saved[0].scenario(date(2024, 1, 1)).evaluation_date,
+            date(2024, 1, 1)
+        );
+        assert_eq!(saved[1].scenario(date(2024, 1, 1)), scenario);
+
+        assert!(matches!(
+            from_json::<Vec<SavedScenario>>("{"),
+            Err(Error::Json(_))
+        ));
+    }
+}
@@ -0,0 +1,544 @@
+//! "What if" calculations, separate from the UI so they can be tested, and
+//! reused by a CLI or a backend.
+use std::fmt::Display;
+
+use chrono::{Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bitcoin::{BitcoinAmount, MilliSatAmount},
+    dollar::{DollarAmount, SignedDollarAmount},
+    fees::{self, FeeModel},
+    price::{mul_div, Price, Rounding},
+    price_lookup::{PriceHistory, Quote},
+};
+
+/// How the amount is converted to bitcoin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Strategy {
+    /// Everything converted at once on the buy date
+    #[default]
+    LumpSum,
+    /// The amount is converted every period, from the buy date until the
+    /// evaluation date
+    DollarCostAveraging(Frequency),
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 4] = [
+        Strategy::LumpSum,
+        Strategy::DollarCostAveraging(Frequency::Daily),
+        Strategy::DollarCostAveraging(Frequency::Weekly),
+        Strategy::DollarCostAveraging(Frequency::Monthly),
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl Frequency {
+    /// Date of the `n`th period after `start`. Months are counted from the
+    /// start, so buying on the 31st falls back to the end of shorter months
+    /// without drifting.
+    fn nth_date(self, start: NaiveDate, n: u32) -> Option<NaiveDate> {
+        match self {
+            Frequency::Daily => start.checked_add_days(Days::new(n.into())),
+            Frequency::Weekly => start.checked_add_days(Days::new(7 * u64::from(n))),
+            Frequency::Monthly => start.checked_add_months(Months::new(n)),
+        }
+    }
+}
+
+impl Display for Frequency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Frequency::Daily => write!(f, "day"),
+            Frequency::Weekly => write!(f, "week"),
+            Frequency::Monthly => write!(f, "month"),
+        }
+    }
+}
+
+/// How much of the amount goes into bitcoin. The rest is kept in cash, or in
+/// another asset with `Benchmark::hold_rest`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Allocation {
+    /// Percentage of the amount, from 0 to 100
+    Percent(u64),
+    /// Fixed part of the amount, which can't be more than the amount
+    Fixed(DollarAmount),
+}
+
+impl Default for Allocation {
+    fn default() -> Self {
+        Allocation::Percent(100)
+    }
+}
+
+impl Allocation {
+    /// Part of `amount` going into bitcoin, rounded down to the cent.
+    fn of(self, amount: DollarAmount) -> Result<DollarAmount, Error> {
+        match self {
+            Allocation::Percent(percent) if percent <= 100 => {
+                mul_div(amount.cents(), percent, 100, Rounding::Down)
+                    .map(DollarAmount::from_cents)
+                    .ok_or(Error::Overflow)
+            }
+            Allocation::Fixed(fixed) if fixed <= amount => Ok(fixed),
+            _ => Err(Error::InvalidAllocation),
+        }
+    }
+
+    pub fn is_everything(self) -> bool {
+        self == Allocation::Percent(100)
+    }
+
+    pub fn mode(self) -> AllocationMode {
+        match self {
+            Allocation::Percent(_) => AllocationMode::Percent,
+            Allocation::Fixed(_) => AllocationMode::Fixed,
+        }
+    }
+}
+
+/// Whether the allocation input is a percentage or a dollar amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocationMode {
+    Percent,
+    Fixed,
+}
+
+impl AllocationMode {
+    pub const ALL: [AllocationMode; 2] = [AllocationMode::Percent, AllocationMode::Fixed];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scenario {
+    /// Converted once, or every period when dollar-cost averaging
+    pub amount: DollarAmount,
+    /// How much of `amount` goes into bitcoin
+    pub allocation: Allocation,
+    pub buy_date: NaiveDate,
+    /// When the bitcoin is sold, or just valued
+    pub evaluation_date: NaiveDate,
+    pub strategy: Strategy,
+    /// Paid on every purchase, and when selling on the evaluation date
+    pub fees: FeeModel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Purchase {
+    pub date: NaiveDate,
+    /// Converted into bitcoin, without what's kept in cash
+    pub amount: DollarAmount,
+    pub bitcoin_msats: MilliSatAmount,
+    pub quote: Quote,
+}
+
+/// What was bought, which is known even without the evaluation date's price.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Acquisition {
+    /// Everything that was put aside, including what's kept in cash
+    pub invested: DollarAmount,
+    /// Part of `invested` that wasn't converted
+    pub cash: DollarAmount,
+    /// Part of `invested` that was converted, fees included
+    pub invested_in_bitcoin: DollarAmount,
+    pub bitcoin: BitcoinAmount,
+    /// Same as `bitcoin`, before rounding down to the sat
+    pub bitcoin_msats: MilliSatAmount,
+    pub purchases: Vec<Purchase>,
+    /// Purchases skipped because there was no price for their date
+    pub missed_purchases: usize,
+}
+
+impl Acquisition {
+    /// Average price paid for the bitcoin
+    pub fn cost_basis(&self) -> Option<Price> {
+        Price::from_cost(self.invested_in_bitcoin, self.bitcoin)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioResult {
+    pub acquisition: Acquisition,
+    /// Fiat value of the bitcoin on the evaluation date
+    pub bitcoin_value: DollarAmount,
+    /// Value of the whole portfolio: bitcoin and cash
+    pub value: DollarAmount,
+    pub gain: SignedDollarAmount,
+    /// `value / invested`, e.g. 2.0 if the value doubled
+    pub multiple: f64,
+    pub evaluation_quote: Quote,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    MissingPrice(NaiveDate),
+    EvaluationBeforeBuy,
+    NothingInvested,
+    InvalidAllocation,
+    Overflow,
+}
+
+impl From<fees::Error> for Error {
+    fn from(err: fees::Error) -> Self {
+        match err {
+            fees::Error::Overflow => Error::Overflow,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::MissingPrice(date) => write!(f, "no price available for {date}"),
+            Error::EvaluationBeforeBuy => write!(f, "the evaluation date is before the buy date"),
+            Error::NothingInvested => write!(f, "nothing was invested"),
+            Error::InvalidAllocation => write!(f, "can't put more than the amount into bitcoin"),
+            Error::Overflow => write!(f, "amounts are too large"),
+        }
+    }
+}
+
+fn quote(prices: &impl PriceHistory, date: NaiveDate) -> Result<Quote, Error> {
+    prices.quote(date).ok_or(Error::MissingPrice(date))
+}
+
+impl Scenario {
+    /// Same dates, with everything this scenario acquired over time
+    /// converted at once instead, to compare strategies.
+    pub fn as_lump_sum(&self, acquisition: &Acquisition) -> Scenario {
+        Scenario {
+            amount: acquisition.invested,
+            allocation: Allocation::Fixed(acquisition.invested_in_bitcoin),
+            strategy: Strategy::LumpSum,
+            ..*self
+        }
+    }
+
+    /// Same scenario in a frictionless world, to see how much fees cost.
+    pub fn without_fees(&self) -> Scenario {
+        Scenario {
+            fees: FeeModel::default(),
+            ..*self
+        }
+    }
+
+    fn purchase_dates(&self) -> Vec<NaiveDate> {
+        match self.strategy {
+            Strategy::LumpSum => vec![self.buy_date],
+            Strategy::DollarCostAveraging(frequency) => (0..)
+                .map_while(|n| frequency.nth_date(self.buy_date, n))
+                .take_while(|date| *date <= self.evaluation_date)
+                .collect(),
+        }
+    }
+
+    /// Buys bitcoin on each of the strategy's dates. Amounts are added up to
+    /// the msat, so lots of small purchases don't each get rounded down.
+    pub fn acquire(&self, prices: &impl PriceHistory) -> Result<Acquisition, Error> {
+        let dates = self.purchase_dates();
+        let bitcoin_amount = self.allocation.of(self.amount)?;
+        let mut purchases = vec![];
+        for &date in &dates {
+            let quote = match (quote(prices, date), self.strategy) {
+                (Ok(quote), _) => quote,
+                // Gaps in the data shouldn't prevent seeing what all the
+                // other purchases add up to
+                (Err(_), Strategy::DollarCostAveraging(_)) => continue,
+                (Err(err), Strategy::LumpSum) => return Err(err),
+            };
+            let bitcoin_msats = self.fees.buy(bitcoin_amount, quote.price)?;
+
+            purchases.push(Purchase {
+                date,
+                amount: bitcoin_amount,
+                bitcoin_msats,
+                quote,
+            });
+        }
+
+        if purchases.is_empty() {
+            return Err(Error::MissingPrice(self.buy_date));
+        }
+
+        let contributions = purchases.len() as u64;
+        let cash_amount = self.amount.checked_sub(bitcoin_amount);
+        let invested = self.amount.checked_mul(contributions);
+        let cash = cash_amount.and_then(|cash| cash.checked_mul(contributions));
+        let invested_in_bitcoin = bitcoin_amount.checked_mul(contributions);
+        let bitcoin_msats = purchases
+            .iter()
+            .map(|purchase| purchase.bitcoin_msats)
+            .sum::<Option<MilliSatAmount>>()
+            .ok_or(Error::Overflow)?;
+
+        Ok(Acquisition {
+            invested: invested.ok_or(Error::Overflow)?,
+            cash: cash.ok_or(Error::Overflow)?,
+            invested_in_bitcoin: invested_in_bitcoin.ok_or(Error::Overflow)?,
+            bitcoin: bitcoin_msats.to_sats(Rounding::Down),
+            bitcoin_msats,
+            missed_purchases: dates.len() - purchases.len(),
+            purchases,
+        })
+    }
+
+    pub fn evaluate(&self, prices: &impl PriceHistory) -> Result<ScenarioResult, Error> {
+        if self.evaluation_date < self.buy_date {
+            return Err(Error::EvaluationBeforeBuy);
+        }
+
+        let acquisition = self.acquire(prices)?;
+        let evaluation_quote = quote(prices, self.evaluation_date)?;
+        let bitcoin_value = self
+            .fees
+            .sell(acquisition.bitcoin, evaluation_quote.price)?;
+        let value = bitcoin_value
+            .checked_add(acquisition.cash)
+            .ok_or(Error::Overflow)?;
+        let invested = acquisition.invested;
+
+        Ok(ScenarioResult {
+            gain: value.signed_sub(invested).ok_or(Error::Overflow)?,
+            multiple: value.ratio(invested).ok_or(Error::NothingInvested)?,
+            acquisition,
+            bitcoin_value,
+            value,
+            evaluation_quote,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price_lookup::{
+        test_prices::{date, history},
+        Source,
+    };
+
+    #[test]
+    fn test_lump_sum() {
+        let prices = history(&[(date(2017, 1, 1), 1_000), (date(2021, 11, 10), 65_000)]);
+        let scenario = Scenario {
+            amount: DollarAmount::from(10_000),
+            buy_date: date(2017, 1, 1),
+            evaluation_date: date(2021, 11, 10),
+            allocation: Allocation::default(),
+            strategy: Strategy::LumpSum,
+            fees: FeeModel::default(),
+        };
+
+        let result = scenario.evaluate(&prices).unwrap();
+        let acquisition = result.acquisition;
+        assert_eq!(acquisition.invested, DollarAmount::from(10_000));
+        assert_eq!(acquisition.bitcoin, BitcoinAmount::from(1_000_000_000));
+        assert_eq!(acquisition.purchases.len(), 1);
+        assert_eq!(
+            acquisition.purchases[0].quote.price,
+            Price::per_btc(DollarAmount::from(1_000))
+        );
+        assert_eq!(result.value, DollarAmount::from(650_000));
+        assert_eq!(result.gain.to_string(), "+$640,000");
+        assert_eq!(result.multiple, 65.0);
+        assert_eq!(result.evaluation_quote.source, Source::History);
+    }
+
+    #[test]
+    fn test_errors() {
+        let prices = history(&[(date(2017, 1, 1), 1_000)]);
+        let mut scenario = Scenario {
+            amount: DollarAmount::from(10_000),
+            buy_date: date(2017, 1, 1),
+            evaluation_date: date(2018, 1, 1),
+            allocation: Allocation::default(),
+            strategy: Strategy::LumpSum,
+            fees: FeeModel::default(),
+        };
+        assert_eq!(
+            scenario.evaluate(&prices),
+            Err(Error::MissingPrice(date(2018, 1, 1)))
+        );
+        // Knowing what was bought doesn't need the evaluation price
+        assert!(scenario.acquire(&prices).is_ok());
+
+        scenario.evaluation_date = date(2016, 1, 1);
+        assert_eq!(scenario.evaluate(&prices), Err(Error::EvaluationBeforeBuy));
+
+        scenario.evaluation_date = date(2017, 1, 1);
+        scenario.amount = DollarAmount::from(0);
+        assert_eq!(scenario.evaluate(&prices), Err(Error::NothingInvested));
+    }
+
+    #[test]
+    fn test_dollar_cost_averaging() {
+        let prices = history(&[
+            (date(2020, 1, 31), 10_000),
+            (date(2020, 2, 29), 20_000),
+            // No price for 2020-03-31
+            (date(2020, 4, 30), 5_000),
+            (date(2020, 5, 15), 40_000),
+        ]);
+        let scenario = Scenario {
+            amount: DollarAmount::from(100),
+            buy_date: date(2020, 1, 31),
+            evaluation_date: date(2020, 5, 15),
+            allocation: Allocation::default(),
+            strategy: Strategy::DollarCostAveraging(Frequency::Monthly),
+            fees: FeeModel::default(),
+        };
+
+        let result = scenario.evaluate(&prices).unwrap();
+        let acquisition = &result.acquisition;
+        let dates: Vec<NaiveDate> = acquisition.purchases.iter().map(|p| p.date).collect();
+        assert_eq!(
+            dates,
+            vec![date(2020, 1, 31), date(2020, 2, 29), date(2020, 4, 30)]
+        );
+        assert_eq!(acquisition.missed_purchases, 1);
+        assert_eq!(acquisition.invested, DollarAmount::from(300));
+        // 1M + 500k + 2M sats
+        assert_eq!(acquisition.bitcoin, BitcoinAmount::from(3_500_000));
+        assert_eq!(
+            acquisition.cost_basis(),
+            Some(Price::per_btc(DollarAmount::from_cents(857_143)))
+        );
+        assert_eq!(result.value, DollarAmount::from(1_400));
+
+        let lump_sum = scenario.as_lump_sum(acquisition);
+        let lump_sum = lump_sum.evaluate(&prices).unwrap();
+        assert_eq!(lump_sum.acquisition.bitcoin, BitcoinAmount::from(3_000_000));
+        assert_eq!(lump_sum.value, DollarAmount::from(1_200));
+    }
+
+    #[test]
+    fn test_dca_adds_up_msats() {
+        // $1 buys 3 333.333 sats at $30k: each purchase alone would round down
+        let prices = history(&[
+            (date(2020, 1, 1), 30_000),
+            (date(2020, 1, 2), 30_000),
+            (date(2020, 1, 3), 30_000),
+        ]);
+        let scenario = Scenario {
+            amount: DollarAmount::from(1),
+            buy_date: date(2020, 1, 1),
+            evaluation_date: date(2020, 1, 3),
+            allocation: Allocation::default(),
+            strategy: Strategy::DollarCostAveraging(Frequency::Daily),
+            fees: FeeModel::default(),
+        };
+
+        let acquisition = scenario.acquire(&prices).unwrap();
+        assert_eq!(acquisition.bitcoin, BitcoinAmount::from(9_999));
+        assert_eq!(
+            acquisition.bitcoin_msats,
+            MilliSatAmount::from_msats(9_999_999)
+        );
+    }
+
+    #[test]
+    fn test_partial_allocation() {
+        let prices = history(&[(date(2017, 1, 1), 1_000), (date(2021, 11, 10), 65_000)]);
+        let mut scenario = Scenario {
+            amount: DollarAmount::from(10_000),
+            allocation: Allocation::Percent(5),
+            buy_date: date(2017, 1, 1),
+            evaluation_date: date(2021, 11, 10),
+            strategy: Strategy::LumpSum,
+            fees: FeeModel::default(),
+        };
+
+        let result = scenario.evaluate(&prices).unwrap();
+        assert_eq!(result.acquisition.invested, DollarAmount::from(10_000));
+        assert_eq!(result.acquisition.cash, DollarAmount::from(9_500));
+        assert_eq!(
+            result.acquisition.invested_in_bitcoin,
+            DollarAmount::from(500)
+        );
+        assert_eq!(result.acquisition.bitcoin, BitcoinAmount::from(50_000_000));
+        assert_eq!(result.bitcoin_value, DollarAmount::from(32_500));
+        assert_eq!(result.value, DollarAmount::from(42_000));
+        assert_eq!(result.multiple, 4.2);
+
+        scenario.allocation = Allocation::Fixed(DollarAmount::from(1_000));
+        let result = scenario.evaluate(&prices).unwrap();
+        assert_eq!(result.acquisition.cash, DollarAmount::from(9_000));
+        assert_eq!(result.value, DollarAmount::from(74_000));
+
+        scenario.allocation = Allocation::Percent(0);
+        let result = scenario.evaluate(&prices).unwrap();
+        assert_eq!(result.value, DollarAmount::from(10_000));
+        assert_eq!(result.acquisition.cost_basis(), None);
+
+        scenario.allocation = Allocation::Fixed(DollarAmount::from(10_001));
+        assert_eq!(scenario.evaluate(&prices), Err(Error::InvalidAllocation));
+        scenario.allocation = Allocation::Percent(101);
+        assert_eq!(scenario.evaluate(&prices), Err(Error::InvalidAllocation));
+    }
+
+    #[test]
+    fn test_fees() {
+        let prices = history(&[(date(2017, 1, 1), 1_000), (date(2021, 11, 10), 65_000)]);
+        let scenario = Scenario {
+            amount: DollarAmount::from(10_000),
+            allocation: Allocation::default(),
+            buy_date: date(2017, 1, 1),
+            evaluation_date: date(2021, 11, 10),
+            strategy: Strategy::LumpSum,
+            fees: FeeModel {
+                percent_bps: 100,
+                ..Default::default()
+            },
+        };
+
+        let net = scenario.evaluate(&prices).unwrap();
+        let gross = scenario.without_fees().evaluate(&prices).unwrap();
+        assert_eq!(gross.value, DollarAmount::from(650_000));
+        // 1% on the way in, and on the way out
+        assert_eq!(net.acquisition.bitcoin, BitcoinAmount::from(990_000_000));
+        assert_eq!(net.value, DollarAmount::from(637_065));
+
+        // A flat fee doesn't make keeping everything in cash fail
+        let cash_only = Scenario {
+            allocation: Allocation::Percent(0),
+            fees: FeeModel {
+                flat: DollarAmount::from_cents(99),
+                ..Default::default()
+            },
+            ..scenario
+        };
+        let result = cash_only.evaluate(&prices).unwrap();
+        assert_eq!(result.value, DollarAmount::from(10_000));
+
+        // Purchases smaller than the withdrawal fee buy nothing, but the
+        // others still count
+        let prices = history(&[
+            (date(2017, 1, 1), 1_000),
+            (date(2017, 1, 2), 100_000),
+            (date(2017, 1, 3), 1_000),
+        ]);
+        let dca = Scenario {
+            amount: DollarAmount::from(10),
+            evaluation_date: date(2017, 1, 3),
+            strategy: Strategy::DollarCostAveraging(Frequency::Daily),
+            fees: FeeModel {
+                withdrawal: BitcoinAmount::from(50_000),
+                ..Default::default()
+            },
+            ..scenario
+        };
+        let acquisition = dca.acquire(&prices).unwrap();
+        assert_eq!(acquisition.purchases.len(), 3);
+        assert_eq!(
+            acquisition.purchases[1].bitcoin_msats,
+            MilliSatAmount::default()
+        );
+        assert_eq!(acquisition.bitcoin, BitcoinAmount::from(1_900_000));
+    }
+}
@@ -0,0 +1,245 @@
+//! Scenarios encoded in the URL fragment on the web, e.g.
+//! `#amount=10000&currency=USD&buy=2015-01-01&strategy=lump-sum`, so a link
+//! opens the app on the same result. The allocation and fees are only there
+//! when they aren't the defaults: everything in bitcoin, and no fees. The
+//! fragment doesn't reach the server, and changing it doesn't reload the page.
+use chrono::NaiveDate;
+
+use crate::{
+    bitcoin::BitcoinAmount,
+    dollar::{DollarAmount, CURRENCY_CODE},
+    fees::FeeModel,
+    scenario::{Allocation, Frequency, Strategy},
+};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// What's shared in links. Anything missing or invalid is left as the app's
+/// default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SharedScenario {
+    pub amount: Option<DollarAmount>,
+    pub buy_date: Option<NaiveDate>,
+    /// `None` to evaluate at today's price
+    pub evaluation_date: Option<NaiveDate>,
+    pub strategy: Strategy,
+    pub allocation: Allocation,
+    pub fees: FeeModel,
+}
+
+/// e.g. "10000", or "1234.56"
+fn amount_param(amount: DollarAmount) -> String {
+    match amount.cents() % 100 {
+        0 => amount.dollars().to_string(),
+        cents => format!("{}.{cents:02}", amount.dollars()),
+    }
+}
+
+fn strategy_name(strategy: Strategy) -> &'static str {
+    match strategy {
+        Strategy::LumpSum => "lump-sum",
+        Strategy::DollarCostAveraging(Frequency::Daily) => "daily",
+        Strategy::DollarCostAveraging(Frequency::Weekly) => "weekly",
+        Strategy::DollarCostAveraging(Frequency::Monthly) => "monthly",
+    }
+}
+
+impl SharedScenario {
+    pub fn to_fragment(&self) -> String {
+        let mut params = vec![];
+        if let Some(amount) = self.amount {
+            params.push(format!("amount={}", amount_param(amount)));
+            params.push(format!("currency={CURRENCY_CODE}"));
+        }
+        if let Some(date) = self.buy_date {
+            params.push(format!("buy={}", date.format(DATE_FORMAT)));
+        }
+        if let Some(date) = self.evaluation_date {
+            params.push(format!("sell={}", date.format(DATE_FORMAT)));
+        }
+        params.push(format!("strategy={}", strategy_name(self.strategy)));
+        match self.allocation {
+            allocation if allocation.is_everything() => {}
+            Allocation::Percent(percent) => params.push(format!("bitcoin-percent={percent}")),
+            Allocation::Fixed(amount) => {
+                params.push(format!("bitcoin-amount={}", amount_param(amount)))
+            }
+        }
+
+        let fees = self.fees;
+        let fee_params = [
+            ("fee-bps", fees.percent_bps.to_string()),
+            ("flat-fee", amount_param(fees.flat)),
+            ("spread-bps", fees.spread_bps.to_string()),
+            ("withdrawal-sats", fees.withdrawal.sats().to_string()),
+        ];
+        params.extend(
+            fee_params
+                .into_iter()
+                .filter(|(_, value)| value != "0")
+                .map(|(name, value)| format!("{name}={value}")),
+        );
+
+        params.join("&")
+    }
+
+    pub fn from_fragment(fragment: &str) -> Self {
+        let params: Vec<(&str, &str)> = fragment
+            .trim_start_matches('#')
+            .split('&')
+            .filter_map(|param| param.split_once('='))
+            .collect();
+        let param = |name| {
+            params
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+        };
+        let date =
+            |name| param(name).and_then(|value| NaiveDate::parse_from_str(value, DATE_FORMAT).ok());
+        let number = |name| param(name).and_then(|value| value.parse::<u64>().ok());
+
+        // Amounts in another currency can't be shown
+        let currency = param("currency").unwrap_or(CURRENCY_CODE);
+        let dollars = |name| {
+            param(name)
+                .filter(|_| currency == CURRENCY_CODE)
+                .and_then(|value| value.parse::<DollarAmount>().ok())
+        };
+        let allocation = number("bitcoin-percent")
+            .filter(|percent| *percent <= 100)
+            .map(Allocation::Percent)
+            .or_else(|| dollars("bitcoin-amount").map(Allocation::Fixed))
+            .unwrap_or_default();
+        let fees = FeeModel {
+            percent_bps: number("fee-bps").unwrap_or_default(),
+            flat: dollars("flat-fee").unwrap_or_default(),
+            spread_bps: number("spread-bps").unwrap_or_default(),
+            withdrawal: BitcoinAmount::from(number("withdrawal-sats").unwrap_or_default()),
+        };
+
+        Self {
+            amount: dollars("amount"),
+            buy_date: date("buy"),
+            evaluation_date: date("sell"),
+            strategy: param("strategy")
+                .and_then(|value| {
+                    Strategy::ALL
+                        .into_iter()
+                        .find(|strategy| strategy_name(*strategy) == value)
+                })
+                .unwrap_or_default(),
+            allocation,
+            fees,
+        }
+    }
+}
+
+/// Scenario in the page's URL, if any. Nothing to read on desktop.
+pub fn read_url() -> SharedScenario {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let hash = web_sys::window().and_then(|window| window.location().hash().ok());
+        SharedScenario::from_fragment(&hash.unwrap_or_default())
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    SharedScenario::default()
+}
+
+/// Replaces the page's URL without adding to the history, so the back button
+/// still leaves the app.
+pub fn write_url(shared: &SharedScenario) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let url = format!("#{}", shared.to_fragment());
+        let history = web_sys::window().and_then(|window| window.history().ok());
+        if let Some(history) = history {
+            let _ = history.replace_state_with_url(
+                &web_sys::wasm_bindgen::JsValue::NULL,
+                "",
+                Some(&url),
+            );
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = shared;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::price_lookup::test_prices::date;
+
+    #[test]
+    fn test_fragment() {
+        let shared = SharedScenario {
+            amount: Some(DollarAmount::from(10_000)),
+            buy_date: Some(date(2015, 1, 1)),
+            evaluation_date: None,
+            strategy: Strategy::LumpSum,
+            ..SharedScenario::default()
+        };
+        assert_eq!(
+            shared.to_fragment(),
+            "amount=10000&currency=USD&buy=2015-01-01&strategy=lump-sum"
+        );
+        assert_eq!(SharedScenario::from_fragment(&shared.to_fragment()), shared);
+
+        let shared = SharedScenario {
+            amount: Some(DollarAmount::from_cents(123_456)),
+            buy_date: None,
+            evaluation_date: Some(date(2021, 11, 10)),
+            strategy: Strategy::DollarCostAveraging(Frequency::Weekly),
+            ..SharedScenario::default()
+        };
+        assert_eq!(
+            shared.to_fragment(),
+            "amount=1234.56&currency=USD&sell=2021-11-10&strategy=weekly"
+        );
+        assert_eq!(
+            SharedScenario::from_fragment(&format!("#{}", shared.to_fragment())),
+            shared
+        );
+
+        let shared = SharedScenario {
+            allocation: Allocation::Fixed(DollarAmount::from_cents(50_050)),
+            fees: FeeModel {
+                percent_bps: 150,
+                flat: DollarAmount::from(1),
+                spread_bps: 0,
+                withdrawal: BitcoinAmount::from(5_000),
+            },
+            ..SharedScenario::default()
+        };
+        assert_eq!(
+            shared.to_fragment(),
+            "strategy=lump-sum&bitcoin-amount=500.50&fee-bps=150&flat-fee=1&withdrawal-sats=5000"
+        );
+        assert_eq!(SharedScenario::from_fragment(&shared.to_fragment()), shared);
+
+        let shared = SharedScenario {
+            allocation: Allocation::Percent(40),
+            ..SharedScenario::default()
+        };
+        assert_eq!(shared.to_fragment(), "strategy=lump-sum&bitcoin-percent=40");
+        assert_eq!(SharedScenario::from_fragment(&shared.to_fragment()), shared);
+    }
+
+    #[test]
+    fn test_invalid_fragment() {
+        assert_eq!(SharedScenario::from_fragment(""), SharedScenario::default());
+        assert_eq!(
+            SharedScenario::from_fragment(
+                "amount=abc&buy=2015-13-01&strategy=yearly&bitcoin-percent=101&fee-bps=-1&foo"
+            ),
+            SharedScenario::default()
+        );
+        assert_eq!(
+            SharedScenario::from_fragment("amount=100&currency=EUR&buy=2015-01-01"),
+            SharedScenario {
+                buy_date: Some(date(2015, 1, 1)),
+                ..SharedScenario::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Small JSON documents kept between sessions. They're stored in a file in the
+//! platform's directory for app settings on desktop, e.g.
+//! `~/.config/whatif/preferences.json`, and in the browser's local storage on
+//! the web, e.g. under `whatif.preferences`.
+use std::fmt::Display;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{ffi::OsString, path::PathBuf};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+#[derive(Debug)]
+pub enum Error {
+    Json(serde_json::Error),
+    #[cfg(not(target_arch = "wasm32"))]
+    Io(String, std::io::Error),
+    /// Local storage is unavailable, e.g. in private browsing
+    #[cfg(target_arch = "wasm32")]
+    Storage,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Json(err) => write!(f, "invalid JSON: {err}"),
+            #[cfg(not(target_arch = "wasm32"))]
+            Error::Io(path, err) => write!(f, "accessing {path}: {err}"),
+            #[cfg(target_arch = "wasm32")]
+            Error::Storage => write!(f, "local storage is unavailable"),
+        }
+    }
+}
+
+pub fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, Error> {
+    serde_json::from_str(json).map_err(Error::Json)
+}
+
+pub fn to_json<T: Serialize>(value: &T) -> Result<String, Error> {
+    serde_json::to_string_pretty(value).map_err(Error::Json)
+}
+
+/// `whatif` in `%APPDATA%` on Windows, `~/Library/Application Support` on
+/// macOS, and `$XDG_CONFIG_HOME` or `~/.config` elsewhere. The working
+/// directory if none of them is set.
+#[cfg(not(target_arch = "wasm32"))]
+fn directory(var: impl Fn(&'static str) -> Option<OsString>) -> PathBuf {
+    let var = |name| {
+        var(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+    let base = if cfg!(windows) {
+        var("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        var("XDG_CONFIG_HOME").or_else(|| var("HOME").map(|home| home.join(".config")))
+    };
+
+    base.map(|base| base.join("whatif")).unwrap_or_default()
+}
+
+/// A file of the app's directory, e.g. one the user put there to replace
+/// bundled data
+#[cfg(not(target_arch = "wasm32"))]
+pub fn file(file_name: &str) -> PathBuf {
+    directory(std::env::var_os).join(file_name)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn path(name: &str) -> PathBuf {
+    file(&format!("{name}.json"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn io_error(name: &str) -> impl FnOnce(std::io::Error) -> Error + '_ {
+    move |err| Error::Io(path(name).display().to_string(), err)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Result<web_sys::Storage, Error> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .ok_or(Error::Storage)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn key(name: &str) -> String {
+    format!("whatif.{name}")
+}
+
+/// What was stored under `name`, or `None` if nothing was stored yet.
+pub fn load<T: DeserializeOwned>(name: &str) -> Result<Option<T>, Error> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let json = match std::fs::read_to_string(path(name)) {
+        Ok(json) => Some(json),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => return Err(io_error(name)(err)),
+    };
+    #[cfg(target_arch = "wasm32")]
+    let json = local_storage()?
+        .get_item(&key(name))
+        .map_err(|_| Error::Storage)?;
+
+    json.map(|json| from_json(&json)).transpose()
+}
+
+/// Replaces what was stored under `name`.
+pub fn store<T: Serialize>(name: &str, value: &T) -> Result<(), Error> {
+    let json = to_json(value)?;
+    #[cfg(not(target_arch = "wasm32"))]
+    return std::fs::create_dir_all(directory(std::env::var_os))
+        .and_then(|()| std::fs::write(path(name), json))
+        .map_err(io_error(name));
+    #[cfg(target_arch = "wasm32")]
+    return local_storage()?
+        .set_item(&key(name), &json)
+        .map_err(|_| Error::Storage);
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directory() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| OsString::from(value))
+            }
+        };
+        assert_eq!(directory(env(&[])), PathBuf::new());
+
+        if cfg!(all(unix, not(target_os = "macos"))) {
+            assert_eq!(
+                directory(env(&[("HOME", "/home/satoshi")])),
+                PathBuf::from("/home/satoshi/.config/whatif")
+            );
+            assert_eq!(
+                directory(env(&[
+                    ("HOME", "/home/satoshi"),
+                    ("XDG_CONFIG_HOME", "/config")
+                ])),
+                PathBuf::from("/config/whatif")
+            );
+            assert_eq!(
+                directory(env(&[("HOME", "/home/satoshi"), ("XDG_CONFIG_HOME", "")])),
+                PathBuf::from("/home/satoshi/.config/whatif")
+            );
+        }
+    }
+}
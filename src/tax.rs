@@ -0,0 +1,478 @@
+//! Rough capital gains tax estimate when selling on the evaluation date.
+//! Rates are applied to the gain alone, as if there were no other income, so
+//! this is only an illustration of how much tax could eat into the result.
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::storage;
+use crate::{
+    dollar::{DollarAmount, SignedDollarAmount},
+    fees::BASIS_POINTS,
+    price::{mul_div, Rounding},
+    scenario::{Purchase, ScenarioResult},
+};
+
+// Makes it work more easily on WASM + other platforms
+const TAX_PRESETS: &[u8] = include_bytes!("../data/tax_presets.csv");
+/// Replaces the bundled presets when found in the app's directory, next to
+/// the stored preferences
+#[cfg(not(target_arch = "wasm32"))]
+const LOCAL_TAX_PRESETS: &str = "tax_presets.csv";
+
+/// Marginal rate applied to the part of the gain above `from`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bracket {
+    pub from: DollarAmount,
+    pub rate_bps: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Rates {
+    /// Sorted by `from`
+    brackets: Vec<Bracket>,
+}
+
+impl Rates {
+    pub fn flat(rate_bps: u64) -> Self {
+        Self::brackets(vec![Bracket {
+            from: DollarAmount::default(),
+            rate_bps,
+        }])
+    }
+
+    pub fn brackets(mut brackets: Vec<Bracket>) -> Self {
+        brackets.sort_by_key(|bracket| bracket.from);
+        Self { brackets }
+    }
+
+    pub fn tax(&self, gain: DollarAmount) -> Option<DollarAmount> {
+        let gain = gain.cents();
+        let mut tax = 0u64;
+        for (i, bracket) in self.brackets.iter().enumerate() {
+            let upper = match self.brackets.get(i + 1) {
+                Some(next) => next.from.cents().min(gain),
+                None => gain,
+            };
+            let taxed = upper.saturating_sub(bracket.from.cents());
+            tax = tax.checked_add(mul_div(
+                taxed,
+                bracket.rate_bps,
+                BASIS_POINTS,
+                Rounding::Nearest,
+            )?)?;
+        }
+
+        Some(DollarAmount::from_cents(tax))
+    }
+}
+
+/// Tax rules of a jurisdiction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaxRules {
+    pub name: String,
+    /// Gains on bitcoin held for more days than this are long term. `None`
+    /// if the holding period doesn't matter.
+    pub long_term_after_days: Option<u64>,
+    pub short_term: Rates,
+    pub long_term: Rates,
+}
+
+impl TaxRules {
+    pub fn none() -> Self {
+        TaxRules {
+            name: String::from("No tax"),
+            long_term_after_days: None,
+            short_term: Rates::default(),
+            long_term: Rates::default(),
+        }
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.short_term.brackets.is_empty() && self.long_term.brackets.is_empty()
+    }
+
+    fn is_long_term(&self, bought: NaiveDate, sold: NaiveDate) -> bool {
+        self.long_term_after_days
+            .is_some_and(|days| (sold - bought).num_days() > days as i64)
+    }
+
+    /// Tax on selling part of the bitcoin, lot by lot in the order of the
+    /// cost basis method. Sell fees are shared out in proportion to what's
+    /// sold.
+    pub fn estimate(&self, result: &ScenarioResult, sale: Sale) -> Result<TaxEstimate, Error> {
+        if sale.percent > 100 {
+            return Err(Error::InvalidSale);
+        }
+
+        let acquisition = &result.acquisition;
+        let mut lots: Vec<&Purchase> = acquisition
+            .purchases
+            .iter()
+            .filter(|purchase| purchase.bitcoin_msats.msats() > 0)
+            .collect();
+        sale.method.sort(&mut lots);
+
+        let sold = mul_div(
+            acquisition.bitcoin_msats.msats(),
+            sale.percent,
+            100,
+            Rounding::Down,
+        )
+        .ok_or(Error::Overflow)?;
+        let proceeds = mul_div(
+            result.bitcoin_value.cents(),
+            sale.percent,
+            100,
+            Rounding::Down,
+        )
+        .ok_or(Error::Overflow)?;
+
+        let mut remaining = sold;
+        let mut cost = 0u64;
+        let (mut short_term_gain, mut long_term_gain) = (0i64, 0i64);
+        for lot in lots {
+            if remaining == 0 {
+                break;
+            }
+            let lot_msats = lot.bitcoin_msats.msats();
+            let taken = remaining.min(lot_msats);
+            remaining -= taken;
+
+            let lot_cost = mul_div(lot.amount.cents(), taken, lot_msats, Rounding::Nearest)
+                .ok_or(Error::Overflow)?;
+            let lot_proceeds =
+                mul_div(proceeds, taken, sold, Rounding::Nearest).ok_or(Error::Overflow)?;
+            let gain = signed(lot_proceeds)?
+                .checked_sub(signed(lot_cost)?)
+                .ok_or(Error::Overflow)?;
+            let total = match self.is_long_term(lot.date, sale.date) {
+                true => &mut long_term_gain,
+                false => &mut short_term_gain,
+            };
+            *total = total.checked_add(gain).ok_or(Error::Overflow)?;
+            cost = cost.checked_add(lot_cost).ok_or(Error::Overflow)?;
+        }
+
+        // Losses in one category offset gains in the other
+        let (short_term_taxed, long_term_taxed) = match (short_term_gain, long_term_gain) {
+            (short, long) if short < 0 => (0, long.saturating_add(short).max(0)),
+            (short, long) if long < 0 => (short.saturating_add(long).max(0), 0),
+            (short, long) => (short, long),
+        };
+        let taxed = |gain: i64| DollarAmount::from_cents(gain.unsigned_abs());
+        let tax = self
+            .short_term
+            .tax(taxed(short_term_taxed))
+            .zip(self.long_term.tax(taxed(long_term_taxed)))
+            .and_then(|(short, long)| short.checked_add(long))
+            .ok_or(Error::Overflow)?;
+
+        Ok(TaxEstimate {
+            proceeds: DollarAmount::from_cents(proceeds),
+            cost: DollarAmount::from_cents(cost),
+            short_term_gain: SignedDollarAmount::from_cents(short_term_gain),
+            long_term_gain: SignedDollarAmount::from_cents(long_term_gain),
+            tax,
+            after_tax_value: result.value.checked_sub(tax).ok_or(Error::Overflow)?,
+        })
+    }
+}
+
+fn signed(cents: u64) -> Result<i64, Error> {
+    i64::try_from(cents).map_err(|_| Error::Overflow)
+}
+
+/// Which lots are sold first, which only matters when selling part of what
+/// was bought over time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CostBasisMethod {
+    /// First in, first out
+    #[default]
+    Fifo,
+    /// Last in, first out
+    Lifo,
+    /// Highest cost first, to minimize the gain
+    Hifo,
+}
+
+impl CostBasisMethod {
+    pub const ALL: [CostBasisMethod; 3] = [
+        CostBasisMethod::Fifo,
+        CostBasisMethod::Lifo,
+        CostBasisMethod::Hifo,
+    ];
+
+    fn sort(self, lots: &mut [&Purchase]) {
+        match self {
+            CostBasisMethod::Fifo => lots.sort_by_key(|lot| lot.date),
+            CostBasisMethod::Lifo => lots.sort_by_key(|lot| std::cmp::Reverse(lot.date)),
+            // Compares amount / msats without dividing
+            CostBasisMethod::Hifo => lots.sort_by(|a, b| {
+                let cost = |lot: &Purchase, other: &Purchase| {
+                    lot.amount.cents() as u128 * other.bitcoin_msats.msats() as u128
+                };
+                cost(b, a).cmp(&cost(a, b))
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sale {
+    pub date: NaiveDate,
+    /// Part of the bitcoin that is sold, from 0 to 100
+    pub percent: u64,
+    pub method: CostBasisMethod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaxEstimate {
+    /// What the sold bitcoin brought in, after fees
+    pub proceeds: DollarAmount,
+    /// What the sold bitcoin cost
+    pub cost: DollarAmount,
+    pub short_term_gain: SignedDollarAmount,
+    pub long_term_gain: SignedDollarAmount,
+    pub tax: DollarAmount,
+    /// Value of the portfolio once the tax is paid
+    pub after_tax_value: DollarAmount,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Can't sell more than 100%
+    InvalidSale,
+    Overflow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Term {
+    Short,
+    Long,
+    /// Same rates whatever the holding period
+    Any,
+}
+
+#[derive(Deserialize)]
+//"Jurisdiction","Long term after days","Term","From","Rate %"
+struct CsvRecord {
+    #[serde(rename = "Jurisdiction")]
+    jurisdiction: String,
+    #[serde(rename = "Long term after days")]
+    long_term_after_days: Option<u64>,
+    #[serde(rename = "Term")]
+    term: Term,
+    #[serde(rename = "From")]
+    from: u32,
+    #[serde(deserialize_with = "deserialize_bps", rename = "Rate %")]
+    rate_bps: u64,
+}
+
+fn deserialize_bps<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let percent: f64 = Deserialize::deserialize(deserializer)?;
+    Ok((percent * 100.0).round() as u64)
+}
+
+/// Reads one bracket per line, grouped by jurisdiction in the order they
+/// first appear.
+pub fn get_tax_rules_from_csv(reader: impl std::io::Read) -> Result<Vec<TaxRules>, csv::Error> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let mut jurisdictions: Vec<(TaxRules, Vec<Bracket>, Vec<Bracket>)> = vec![];
+    for record in reader.deserialize() {
+        let record: CsvRecord = record?;
+        let index = match jurisdictions
+            .iter()
+            .position(|(rules, _, _)| rules.name == record.jurisdiction)
+        {
+            Some(index) => index,
+            None => {
+                let rules = TaxRules {
+                    name: record.jurisdiction,
+                    long_term_after_days: record.long_term_after_days,
+                    ..TaxRules::none()
+                };
+                jurisdictions.push((rules, vec![], vec![]));
+                jurisdictions.len() - 1
+            }
+        };
+
+        let (_, short_term, long_term) = &mut jurisdictions[index];
+        let bracket = Bracket {
+            from: DollarAmount::from(record.from),
+            rate_bps: record.rate_bps,
+        };
+        match record.term {
+            Term::Short => short_term.push(bracket),
+            Term::Long => long_term.push(bracket),
+            Term::Any => {
+                short_term.push(bracket);
+                long_term.push(bracket);
+            }
+        }
+    }
+
+    Ok(jurisdictions
+        .into_iter()
+        .map(|(rules, short_term, long_term)| TaxRules {
+            short_term: Rates::brackets(short_term),
+            long_term: Rates::brackets(long_term),
+            ..rules
+        })
+        .collect())
+}
+
+/// Presets from `tax_presets.csv` in the app's directory, e.g.
+/// `~/.config/whatif/tax_presets.csv`. `None` if there's no such file, and
+/// always on the web.
+pub fn local_presets() -> Result<Option<Vec<TaxRules>>, csv::Error> {
+    #[cfg(not(target_arch = "wasm32"))]
+    return match std::fs::File::open(storage::file(LOCAL_TAX_PRESETS)) {
+        Ok(file) => get_tax_rules_from_csv(file).map(Some),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    };
+    #[cfg(target_arch = "wasm32")]
+    return Ok(None);
+}
+
+/// No tax, then the `local` presets if there are some, or the bundled ones.
+pub fn presets(local: Option<Vec<TaxRules>>) -> Vec<TaxRules> {
+    let presets = local
+        .or_else(|| get_tax_rules_from_csv(TAX_PRESETS).ok())
+        .unwrap_or_default();
+
+    std::iter::once(TaxRules::none()).chain(presets).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fees::FeeModel,
+        price_lookup::test_prices::{date, history},
+        scenario::{Allocation, Frequency, Scenario, Strategy},
+    };
+
+    #[test]
+    fn test_rates() {
+        assert_eq!(
+            Rates::flat(3_000).tax(DollarAmount::from(1_000)),
+            Some(DollarAmount::from(300))
+        );
+        assert_eq!(
+            Rates::default().tax(DollarAmount::from(1_000)),
+            Some(DollarAmount::default())
+        );
+
+        let rates = Rates::brackets(vec![
+            Bracket {
+                from: DollarAmount::from(47_025),
+                rate_bps: 1_500,
+            },
+            Bracket {
+                from: DollarAmount::from(0),
+                rate_bps: 0,
+            },
+            Bracket {
+                from: DollarAmount::from(518_900),
+                rate_bps: 2_000,
+            },
+        ]);
+        assert_eq!(
+            rates.tax(DollarAmount::from(40_000)),
+            Some(DollarAmount::default())
+        );
+        // 15% of $52,975
+        assert_eq!(
+            rates.tax(DollarAmount::from(100_000)),
+            Some(DollarAmount::from_cents(794_625))
+        );
+    }
+
+    #[test]
+    fn test_cost_basis_methods() {
+        // Three $1,000 lots: 10M sats held long term, 2M sats bought at the
+        // top and 2.5M sats bought on the sell date
+        let prices = history(&[
+            (date(2020, 1, 1), 10_000),
+            (date(2020, 6, 1), 50_000),
+            (date(2021, 6, 1), 40_000),
+        ]);
+        let scenario = Scenario {
+            amount: DollarAmount::from(1_000),
+            allocation: Allocation::default(),
+            buy_date: date(2020, 1, 1),
+            evaluation_date: date(2021, 6, 1),
+            strategy: Strategy::DollarCostAveraging(Frequency::Monthly),
+            fees: FeeModel::default(),
+        };
+        let result = scenario.evaluate(&prices).unwrap();
+        assert_eq!(result.value, DollarAmount::from(5_800));
+
+        let rules = TaxRules {
+            name: String::from("Test"),
+            long_term_after_days: Some(365),
+            short_term: Rates::flat(3_000),
+            long_term: Rates::flat(1_000),
+        };
+        let mut sale = Sale {
+            date: date(2021, 6, 1),
+            percent: 25,
+            method: CostBasisMethod::Fifo,
+        };
+
+        // 3.625M sats from the first lot, which cost $362.50
+        let estimate = rules.estimate(&result, sale).unwrap();
+        assert_eq!(estimate.proceeds, DollarAmount::from(1_450));
+        assert_eq!(estimate.cost, DollarAmount::from_cents(36_250));
+        assert_eq!(estimate.long_term_gain.to_string(), "+$1,087.50");
+        assert_eq!(estimate.short_term_gain.cents(), 0);
+        assert_eq!(estimate.tax, DollarAmount::from_cents(10_875));
+        assert_eq!(estimate.after_tax_value, DollarAmount::from_cents(569_125));
+
+        // All of the last lot, then 1.125M sats of the second one at a loss
+        sale.method = CostBasisMethod::Lifo;
+        let estimate = rules.estimate(&result, sale).unwrap();
+        assert_eq!(estimate.short_term_gain.to_string(), "-$112.50");
+        assert_eq!(estimate.tax, DollarAmount::default());
+
+        // All of the second lot, then 1.625M sats of the last one
+        sale.method = CostBasisMethod::Hifo;
+        let estimate = rules.estimate(&result, sale).unwrap();
+        assert_eq!(estimate.short_term_gain.to_string(), "-$200");
+        assert_eq!(estimate.cost, DollarAmount::from(1_650));
+
+        // The short term loss offsets part of the long term gain
+        sale.percent = 100;
+        let estimate = rules.estimate(&result, sale).unwrap();
+        assert_eq!(estimate.short_term_gain.to_string(), "-$200");
+        assert_eq!(estimate.long_term_gain.to_string(), "+$3,000");
+        assert_eq!(estimate.tax, DollarAmount::from(280));
+
+        sale.percent = 101;
+        assert_eq!(rules.estimate(&result, sale), Err(Error::InvalidSale));
+        assert!(TaxRules::none().estimate(&result, sale).is_err());
+    }
+
+    #[test]
+    fn test_loading_csv() {
+        let presets = get_tax_rules_from_csv(TAX_PRESETS).unwrap();
+        assert!(!presets.is_empty());
+
+        let france = presets
+            .iter()
+            .find(|rules| rules.name.starts_with("France"))
+            .unwrap();
+        assert_eq!(france.long_term_after_days, None);
+        assert_eq!(france.short_term, Rates::flat(3_000));
+        assert_eq!(france.long_term, Rates::flat(3_000));
+
+        assert!(super::presets(None).first().unwrap().is_none());
+        assert_eq!(super::presets(Some(vec![])), [TaxRules::none()]);
+    }
+}
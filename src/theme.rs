@@ -0,0 +1,137 @@
+//! Light and dark themes, with bitcoin orange for accents, and green and red
+//! for gains and losses.
+
+use iced::{theme::Palette, Color, Subscription, Theme};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemeMode {
+    Light,
+    Dark,
+    /// Follows the preference of the system, or of the browser on the web.
+    /// Only the browser's changes are followed live, see `system_changes`.
+    #[default]
+    System,
+}
+
+impl ThemeMode {
+    pub const ALL: [ThemeMode; 3] = [ThemeMode::Light, ThemeMode::Dark, ThemeMode::System];
+
+    pub fn is_dark(self) -> bool {
+        match self {
+            ThemeMode::Light => false,
+            ThemeMode::Dark => true,
+            // Dark was the only theme before there was a choice
+            ThemeMode::System => prefers_dark().unwrap_or(true),
+        }
+    }
+
+    pub fn theme(self) -> Theme {
+        match self.is_dark() {
+            true => Theme::custom(String::from("Bitcoin dark"), dark_palette()),
+            false => Theme::custom(String::from("Bitcoin light"), light_palette()),
+        }
+    }
+}
+
+fn bitcoin_orange() -> Color {
+    Color::from_rgb8(0xF7, 0x93, 0x1A)
+}
+
+pub fn dark_palette() -> Palette {
+    Palette {
+        background: Color::from_rgb8(0x1B, 0x1B, 0x1F),
+        text: Color::from_rgb8(0xE6, 0xE6, 0xE6),
+        primary: bitcoin_orange(),
+        success: Color::from_rgb8(0x3F, 0xB9, 0x50),
+        danger: Color::from_rgb8(0xF8, 0x51, 0x49),
+    }
+}
+
+pub fn light_palette() -> Palette {
+    Palette {
+        background: Color::from_rgb8(0xFA, 0xFA, 0xFA),
+        text: Color::from_rgb8(0x1B, 0x1B, 0x1F),
+        primary: bitcoin_orange(),
+        success: Color::from_rgb8(0x1A, 0x7F, 0x37),
+        danger: Color::from_rgb8(0xCF, 0x22, 0x2E),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn dark_query() -> Option<web_sys::MediaQueryList> {
+    web_sys::window()?
+        .match_media("(prefers-color-scheme: dark)")
+        .ok()?
+}
+
+/// Whether the system or browser asks for a dark theme, if it can be told
+#[cfg(target_arch = "wasm32")]
+fn prefers_dark() -> Option<bool> {
+    Some(dark_query()?.matches())
+}
+
+/// Fires whenever the browser switches between light and dark, so
+/// `ThemeMode::System` can follow it.
+#[cfg(target_arch = "wasm32")]
+pub fn system_changes() -> Subscription<()> {
+    use iced::futures::{channel::mpsc, SinkExt, StreamExt};
+    use web_sys::wasm_bindgen::{closure::Closure, JsCast};
+
+    struct SystemChanges;
+    iced::subscription::channel(
+        std::any::TypeId::of::<SystemChanges>(),
+        1,
+        |mut output| async move {
+            let (sender, mut changes) = mpsc::unbounded();
+            // Lives as long as the subscription, which never returns
+            let on_change = Closure::<dyn FnMut()>::new(move || {
+                let _ = sender.unbounded_send(());
+            });
+            if let Some(query) = dark_query() {
+                query.set_onchange(Some(on_change.as_ref().unchecked_ref()));
+            }
+
+            loop {
+                changes.next().await;
+                let _ = output.send(()).await;
+            }
+        },
+    )
+}
+
+/// Nothing on desktop: the system is only asked when the theme is picked or
+/// the app starts, by running `defaults`, `reg` or `gsettings`, so a change
+/// of the system theme shows after a restart.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn system_changes() -> Subscription<()> {
+    Subscription::none()
+}
+
+/// Whether the system or browser asks for a dark theme, if it can be told.
+/// Runs a command synchronously, so it's only a snapshot.
+#[cfg(not(target_arch = "wasm32"))]
+fn prefers_dark() -> Option<bool> {
+    let output = |program: &str, args: &[&str]| {
+        let output = std::process::Command::new(program)
+            .args(args)
+            .output()
+            .ok()?;
+        Some(String::from_utf8_lossy(&output.stdout).to_lowercase())
+    };
+
+    if cfg!(target_os = "macos") {
+        // Only set in dark mode
+        Some(output("defaults", &["read", "-g", "AppleInterfaceStyle"])?.contains("dark"))
+    } else if cfg!(target_os = "windows") {
+        let key = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize";
+        let output = output("reg", &["query", key, "/v", "AppsUseLightTheme"])?;
+        Some(output.contains("0x0"))
+    } else {
+        let output = output(
+            "gsettings",
+            &["get", "org.gnome.desktop.interface", "color-scheme"],
+        )?;
+        (!output.is_empty()).then(|| output.contains("dark"))
+    }
+}
@@ -5,46 +5,1029 @@ use std::sync::{mpsc::Receiver, Arc, Mutex};
 use chrono::{NaiveDate, Utc};
 use iced::{
     executor,
-    widget::{text, Button, Column, Container, Text},
-    Application, Command, Element, Length, Settings, Subscription, Theme,
+    keyboard::{self, key::Named, Key},
+    widget::{
+        checkbox, focus_next, focus_previous, pick_list, text, text_input, Button, Column,
+        Container, Row, Text,
+    },
+    Application, Color, Command, Element, Length, Settings, Subscription, Theme,
 };
-use iced_aw::date_picker::Date;
 
 use crate::{
-    bitcoin::BitcoinAmount, dollar::DollarAmount, numeric_input::numeric_input,
-    price_lookup::PriceDatabase,
+    a11y,
+    benchmark::{self, Asset, Benchmark},
+    bitcoin::{BitcoinAmount, BitcoinFormat, BitcoinUnit, Segment},
+    bitcoin_text::bitcoin_text,
+    chart::{line_chart, Marker, Series},
+    date_picker::date_picker,
+    date_presets::{self, DatePreset},
+    dollar::{DollarAmount, Overflow, SignedDollarAmount},
+    fees::{FeeModel, FeePreset},
+    i18n::{Choice, Language, Msg},
+    inflation::{Cpi, Dollars},
+    metrics::{daily_values, DailyValue, Metrics},
+    numeric_input::numeric_input,
+    preferences::Preferences,
+    price_lookup::{Lookup, PriceDatabase, PriceHistory},
+    rebalancing::{RebalanceRule, Rebalancing},
+    saved::{self, SavedScenario},
+    scenario::{Acquisition, Allocation, AllocationMode, Scenario, ScenarioResult, Strategy},
+    share::{self, SharedScenario},
+    tax::{self, CostBasisMethod, Sale, TaxRules},
+    theme::{self, ThemeMode},
 };
 
 pub struct WhatIf {
     amount: Option<DollarAmount>,
-    show_date_picker: bool,
+    /// Set when an amount typed in doesn't fit, instead of capping it
+    amount_error: Option<Overflow>,
     start_date: Option<NaiveDate>,
+    notable_dates: Vec<DatePreset>,
+    /// `None` to evaluate at today's price
+    evaluation_date: Option<NaiveDate>,
+    bitcoin_format: BitcoinFormat,
+    sub_sat_precision: bool,
+    strategy: Strategy,
+    allocation: Allocation,
+    /// Index of the benchmark asset that what isn't converted is kept in,
+    /// `None` for cash
+    rest_in: Option<usize>,
+    rebalance_rule: RebalanceRule,
+    fee_preset: FeePreset,
+    tax_presets: Vec<TaxRules>,
+    /// Index in `tax_presets`, the first of which is no tax
+    tax_preset: usize,
+    cost_basis_method: CostBasisMethod,
+    /// Part of the bitcoin sold on the evaluation date, for the tax estimate
+    sell_percent: u64,
+    cpi: Cpi,
+    dollars: Dollars,
+    benchmark_assets: Vec<Asset>,
+    savings_rate_percent: u64,
+    /// For the Sharpe ratio
+    risk_free_percent: u64,
+    log_scale: bool,
+    saved_scenarios: Vec<SavedScenario>,
+    /// Name to save the current scenario under
+    scenario_name: String,
+    preferences: Preferences,
+    /// From the preferences, kept so the system isn't asked on every redraw
+    theme: Theme,
+    language: Language,
+    /// What the scenario would be worth, then its details one per line. The
+    /// first line is in the window title, and all of it is read out by screen
+    /// readers when it changes.
+    summary: Option<String>,
     price_database: PriceDatabase,
     updates_receiver: Arc<Mutex<Receiver<NaiveDate>>>,
 }
 
 impl WhatIf {
-    pub fn bitcoin_amount(&self) -> Option<BitcoinAmount> {
-        let amount = self.amount?.dollars();
-        let start_date = self.start_date?;
-        let (usd, sats) = self.price_database.get(start_date)?;
-        println!("Found the date's rates: {} = {}", usd, sats);
+    /// The scenario the user is building, once they've filled everything in
+    pub fn scenario(&self) -> Option<Scenario> {
+        Some(Scenario {
+            amount: self.amount?,
+            allocation: self.allocation,
+            buy_date: self.start_date?,
+            evaluation_date: self.evaluation_date(),
+            strategy: self.strategy,
+            fees: self.fee_preset.fees,
+        })
+    }
+
+    /// Buy and sell dates can only be picked where there's a price. Today's
+    /// can be sold at even before it's fetched.
+    fn date_pickers(&self) -> Option<Row<'_, Message>> {
+        let dates = self.price_database.dates()?;
+        let today = Utc::now().date_naive();
+        let has_price = |date| self.price_database.quote(date).is_some();
+
+        let buy_date = date_picker(
+            self.language.tr(Msg::BuyDate),
+            self.start_date,
+            dates.clone(),
+            has_price,
+            Message::DateSelected,
+        )
+        .placeholder(self.language.tr(Msg::DateFormat))
+        .language(self.language);
+        let sell_date = date_picker(
+            self.language.tr(Msg::SellDate),
+            self.evaluation_date,
+            self.start_date.unwrap_or(*dates.start())..=today.max(*dates.end()),
+            move |date| date == today || has_price(date),
+            Message::EvaluationDateSelected,
+        )
+        .placeholder(self.language.tr(Msg::Today))
+        .language(self.language);
+
+        Some(
+            Row::new()
+                .spacing(10)
+                .align_items(iced::Alignment::Start)
+                .push(buy_date)
+                .push(sell_date)
+                .push_maybe(self.evaluation_date.map(|_| {
+                    Button::new(Text::new(self.language.tr(Msg::Today)))
+                        .on_press(Message::EvaluationDateReset)
+                })),
+        )
+    }
+
+    /// Each fee of the custom preset, in the unit it's usually quoted in
+    fn custom_fees_view(&self) -> Option<Column<'_, Message>> {
+        if !self.fee_preset.is_custom() {
+            return None;
+        }
+
+        let fees = self.fee_preset.fees;
+        let field = |label, value, step, on_change: fn(FeeModel, u64) -> FeeModel| {
+            Row::new()
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .push(text(self.language.tr(label)).width(Length::FillPortion(1)))
+                .push(
+                    Container::new(numeric_input(Some(value), step, move |value| {
+                        Message::CustomFeesUpdated(on_change(fees, value.unwrap_or_default()))
+                    }))
+                    .width(Length::FillPortion(2)),
+                )
+        };
+
+        Some(
+            Column::new()
+                .spacing(5)
+                .push(field(
+                    Msg::TradingFee,
+                    fees.percent_bps,
+                    5,
+                    |fees, percent_bps| FeeModel {
+                        percent_bps,
+                        ..fees
+                    },
+                ))
+                .push(field(
+                    Msg::Spread,
+                    fees.spread_bps,
+                    5,
+                    |fees, spread_bps| FeeModel { spread_bps, ..fees },
+                ))
+                .push(field(Msg::FlatFee, fees.flat.cents(), 10, |fees, cents| {
+                    FeeModel {
+                        flat: DollarAmount::from_cents(cents),
+                        ..fees
+                    }
+                }))
+                .push(field(
+                    Msg::WithdrawalFee,
+                    fees.withdrawal.sats(),
+                    1_000,
+                    |fees, sats| FeeModel {
+                        withdrawal: BitcoinAmount::from(sats),
+                        ..fees
+                    },
+                )),
+        )
+    }
+
+    /// Buy dates one click away, for those there's a price for
+    fn date_presets_view(&self) -> Row<'_, Message> {
+        let today = Utc::now().date_naive();
+        let has_price = |preset: &DatePreset| self.price_database.quote(preset.date).is_some();
+        let notable_dates: Vec<DatePreset> = self
+            .notable_dates
+            .iter()
+            .filter(|preset| has_price(preset))
+            .cloned()
+            .collect();
+        let selected = notable_dates
+            .iter()
+            .find(|preset| Some(preset.date) == self.start_date)
+            .map(|preset| self.language.choice(*preset, Msg::DatePreset));
+
+        [1, 5]
+            .into_iter()
+            .filter_map(|years| date_presets::years_ago(&self.price_database, today, years))
+            .fold(
+                Row::new().spacing(10).align_items(iced::Alignment::Center),
+                |row, preset| {
+                    row.push(
+                        Button::new(Text::new(self.language.tr(Msg::Occasion(preset.occasion))))
+                            .on_press(Message::DatePresetSelected(preset)),
+                    )
+                },
+            )
+            .push(
+                pick_list(
+                    self.language.choices(notable_dates, Msg::DatePreset),
+                    selected,
+                    |choice: Choice<DatePreset>| Message::DatePresetSelected(choice.value),
+                )
+                .placeholder(self.language.tr(Msg::NotableDates)),
+            )
+    }
+
+    /// Where the price for a buy or sell date is at, unless it's loaded and
+    /// the result shows it
+    fn lookup_view(&self, date: Option<NaiveDate>) -> Option<Element<'_, Message>> {
+        let Some(date) = date else {
+            return Some(text(self.language.tr(Msg::PickBuyDate)).into());
+        };
+        let danger = self.theme().palette().danger;
+
+        match self.price_database.lookup(date) {
+            Lookup::Loaded(_) => None,
+            Lookup::Loading => Some(text(self.language.tr(Msg::Loading(date))).into()),
+            Lookup::Unavailable(reason) => Some(
+                text(self.language.tr(Msg::NoPrice(date, reason)))
+                    .style(danger)
+                    .into(),
+            ),
+            Lookup::Failed(err) => Some(
+                Row::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(text(self.language.tr(Msg::FetchFailed(date, &err))).style(danger))
+                    .push(
+                        Button::new(Text::new(self.language.tr(Msg::Retry)))
+                            .on_press(Message::RetryPrice(date)),
+                    )
+                    .into(),
+            ),
+        }
+    }
+
+    /// What's still needed for a result, so loading, missing data and errors
+    /// can be told apart. `None` once everything is there.
+    fn status_view(&self) -> Option<Column<'_, Message>> {
+        let messages: Vec<Element<'_, Message>> = [
+            match self.amount_error {
+                Some(_) => Some(
+                    text(self.language.tr(Msg::AmountTooLarge))
+                        .style(self.theme().palette().danger)
+                        .into(),
+                ),
+                None => self
+                    .amount
+                    .is_none()
+                    .then(|| text(self.language.tr(Msg::TypeAnAmount)).into()),
+            },
+            // Only the buy date can be missing: the sell date is today by default
+            self.lookup_view(self.start_date),
+            self.lookup_view(Some(self.evaluation_date())),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        (!messages.is_empty()).then(|| {
+            Column::with_children(messages)
+                .spacing(5)
+                .align_items(iced::Alignment::Center)
+        })
+    }
+
+    /// What's kept in the URL on the web
+    fn shared(&self) -> SharedScenario {
+        SharedScenario {
+            amount: self.amount,
+            buy_date: self.start_date,
+            evaluation_date: self.evaluation_date,
+            strategy: self.strategy,
+            allocation: self.allocation,
+            fees: self.fee_preset.fees,
+        }
+    }
+
+    fn evaluation_date(&self) -> NaiveDate {
+        self.evaluation_date
+            .unwrap_or_else(|| Utc::now().date_naive())
+    }
+
+    fn btc_segments(&self, acquisition: &Acquisition) -> Vec<Segment> {
+        if self.sub_sat_precision {
+            acquisition.bitcoin_msats.segments()
+        } else {
+            acquisition.bitcoin.display(self.bitcoin_format).segments()
+        }
+    }
+
+    fn acquisition_view(
+        &self,
+        scenario: &Scenario,
+        acquisition: &Acquisition,
+    ) -> Column<'_, Message> {
+        let (intro, outro) = match scenario.strategy {
+            Strategy::LumpSum => {
+                let quote = acquisition.purchases[0].quote;
+                let intro = if scenario.allocation.is_everything() {
+                    Msg::ConvertedEverything {
+                        invested: acquisition.invested,
+                    }
+                } else {
+                    Msg::ConvertedPart {
+                        bitcoin: acquisition.invested_in_bitcoin,
+                        invested: acquisition.invested,
+                    }
+                };
+                (
+                    self.language.tr(intro),
+                    self.language.tr(Msg::BoughtOn {
+                        date: scenario.buy_date,
+                        price: quote.price,
+                        source: quote.source,
+                    }),
+                )
+            }
+            Strategy::DollarCostAveraging(frequency) => (
+                self.language.tr(Msg::BoughtEvery {
+                    amount: acquisition.purchases[0].amount,
+                    frequency,
+                    since: scenario.buy_date,
+                    invested: acquisition.invested_in_bitcoin,
+                }),
+                acquisition
+                    .cost_basis()
+                    .map(|price| self.language.tr(Msg::AverageCost(price)))
+                    .unwrap_or_default(),
+            ),
+        };
+
+        Column::new()
+            .align_items(iced::Alignment::Center)
+            .push(text(intro).size(30))
+            .push(bitcoin_text(
+                self.btc_segments(acquisition),
+                30,
+                &self.theme(),
+            ))
+            .push(text(outro))
+            .push_maybe((acquisition.missed_purchases > 0).then(|| {
+                text(
+                    self.language
+                        .tr(Msg::SkippedPurchases(acquisition.missed_purchases as u64)),
+                )
+            }))
+            .push_maybe(
+                (!acquisition.bitcoin.is_valid())
+                    .then(|| text(self.language.tr(Msg::MoreThan21Million))),
+            )
+    }
+
+    /// Dollar-cost averaging side by side with investing everything at once
+    fn comparison_view(
+        &self,
+        scenario: &Scenario,
+        result: &ScenarioResult,
+    ) -> Option<Row<'_, Message>> {
+        if scenario.strategy == Strategy::LumpSum {
+            return None;
+        }
+
+        let lump_sum = scenario
+            .as_lump_sum(&result.acquisition)
+            .evaluate(&self.price_database)
+            .ok()?;
+
+        let column = |title: String, result: &ScenarioResult| {
+            let acquisition = &result.acquisition;
+            Column::new()
+                .spacing(5)
+                .width(Length::Fill)
+                .push(text(title).size(20))
+                .push(text(
+                    self.language.tr(Msg::InvestedAmount(acquisition.invested)),
+                ))
+                .push(bitcoin_text(
+                    self.btc_segments(acquisition),
+                    16,
+                    &self.theme(),
+                ))
+                .push_maybe(
+                    acquisition
+                        .cost_basis()
+                        .map(|price| text(self.language.tr(Msg::CostBasis(price)))),
+                )
+                .push(text(self.language.tr(Msg::ValueAmount(result.value))))
+                .push(
+                    text(self.language.tr(Msg::GainAmount(result.gain)))
+                        .style(self.gain_color(result.gain)),
+                )
+        };
+
+        Some(
+            Row::new()
+                .spacing(20)
+                .push(column(
+                    self.language.tr(Msg::Strategy(scenario.strategy)),
+                    result,
+                ))
+                .push(column(
+                    self.language.tr(Msg::Strategy(Strategy::LumpSum)),
+                    &lump_sum,
+                )),
+        )
+    }
+
+    /// Green for gains, red for losses
+    fn gain_color(&self, gain: SignedDollarAmount) -> Color {
+        let palette = self.theme().palette();
+        match gain.is_negative() {
+            true => palette.danger,
+            false => palette.success,
+        }
+    }
+
+    fn value_text(&self, value: DollarAmount) -> String {
+        match self.evaluation_date {
+            None => self.language.tr(Msg::ValueToday(value)),
+            Some(date) => self.language.tr(Msg::ValueOn(date, value)),
+        }
+    }
+
+    /// Announces the result whenever it changes, e.g. once its price arrives
+    fn update_summary(&mut self) {
+        let summary = self
+            .scenario()
+            .and_then(|scenario| {
+                let result = scenario.evaluate(&self.price_database).ok()?;
+                let value = self.value_text(self.blended(&scenario, &result).value);
+                Some(
+                    [value]
+                        .into_iter()
+                        .chain(self.value_details(&scenario, &result)),
+                )
+            })
+            .map(|lines| lines.collect::<Vec<_>>().join("\n"));
+        if summary != self.summary {
+            if let Some(summary) = &summary {
+                a11y::announce(summary);
+            }
+            self.summary = summary;
+        }
+    }
+
+    /// Asset the part that isn't converted is kept in, `None` for cash
+    fn rest_asset(&self) -> Option<&Asset> {
+        self.rest_in
+            .filter(|_| !self.allocation.is_everything())
+            .and_then(|index| self.benchmark_assets.get(index))
+    }
+
+    /// `result` with what isn't converted kept in the chosen asset rather
+    /// than in cash, and what that part is worth. `None` when it's kept in
+    /// cash, or there's no price for the asset on these dates.
+    fn hold_rest(
+        &self,
+        scenario: &Scenario,
+        result: &ScenarioResult,
+    ) -> Option<(ScenarioResult, DollarAmount)> {
+        Benchmark::Asset(self.rest_asset()?)
+            .hold_rest(scenario, result)
+            .ok()
+    }
+
+    /// The whole portfolio, whatever the rest is kept in
+    fn blended(&self, scenario: &Scenario, result: &ScenarioResult) -> ScenarioResult {
+        self.hold_rest(scenario, result)
+            .map_or_else(|| result.clone(), |(blended, _)| blended)
+    }
+
+    /// What the value is in real terms, what it's made of, and what fees took.
+    /// `result` keeps the rest in cash, as `Scenario::evaluate` does.
+    fn value_details(&self, scenario: &Scenario, result: &ScenarioResult) -> Vec<String> {
+        let cash = result.acquisition.cash;
+        let held = self.hold_rest(scenario, result);
+        let composition = match (self.rest_asset(), held.as_ref()) {
+            (Some(asset), Some((_, rest))) => Some(self.language.tr(Msg::BitcoinAndAsset {
+                bitcoin: result.bitcoin_value,
+                rest: *rest,
+                asset: &asset.name,
+            })),
+            (Some(asset), None) => Some(self.language.tr(Msg::RestKeptInCash(&asset.name))),
+            (None, _) => (cash > DollarAmount::default()).then(|| {
+                self.language.tr(Msg::BitcoinAndCash {
+                    bitcoin: result.bitcoin_value,
+                    cash,
+                })
+            }),
+        };
+        let result = &self.blended(scenario, result);
+
+        let before_fees = (!scenario.fees.is_free())
+            .then(|| {
+                let without_fees = scenario.without_fees();
+                let gross = without_fees.evaluate(&self.price_database).ok()?;
+                Some(self.blended(&without_fees, &gross))
+            })
+            .flatten()
+            .map(|gross| {
+                self.language.tr(Msg::BeforeFees {
+                    gross: gross.value,
+                    fees: gross
+                        .value
+                        .signed_sub(result.value)
+                        .unwrap_or_default()
+                        .abs(),
+                })
+            });
+
+        let real = self.dollars.base_date(scenario).and_then(|base_date| {
+            match self.cpi.real_result(scenario, result, base_date) {
+                Some(real) => Some(self.language.tr(Msg::RealValue {
+                    value: real.value,
+                    base_year: real.base_year(),
+                    invested: real.invested,
+                    real_return: real.multiple - 1.0,
+                    nominal_return: result.multiple - 1.0,
+                })),
+                // e.g. selling today, after the last CPI release bundled
+                None => self.cpi.months().map(|months| {
+                    self.language.tr(Msg::NoCpiData {
+                        first: *months.start(),
+                        last: *months.end(),
+                    })
+                }),
+            }
+        });
+
+        [real, composition, before_fees]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    fn value_view(&self, scenario: &Scenario, result: &ScenarioResult) -> Column<'_, Message> {
+        let blended = self.blended(scenario, result);
+        let value = self.value_text(blended.value);
+
+        self.value_details(scenario, result).into_iter().fold(
+            Column::new()
+                .align_items(iced::Alignment::Center)
+                .push(text(value).size(50).style(self.gain_color(blended.gain))),
+            |column, line| column.push(text(line)),
+        )
+    }
+
+    fn chart_markers(&self, scenario: &Scenario) -> Vec<Marker> {
+        vec![
+            Marker {
+                date: scenario.buy_date,
+                label: self.language.tr(Msg::Buy),
+                positive: true,
+            },
+            Marker {
+                date: scenario.evaluation_date,
+                label: self.language.tr(Msg::Sell),
+                positive: false,
+            },
+        ]
+    }
+
+    /// Bitcoin price from the buy date to the evaluation date
+    fn price_chart_view(&self, scenario: &Scenario) -> Option<Element<'_, Message>> {
+        let prices: Vec<_> = self
+            .price_database
+            .series(scenario.buy_date, scenario.evaluation_date)
+            .into_iter()
+            .map(|(date, quote)| (date, quote.price.fiat_per_btc()))
+            .collect();
+        if prices.is_empty() {
+            return None;
+        }
+
+        let series = vec![Series {
+            label: self.language.tr(Msg::Price),
+            points: prices,
+        }];
+        Some(line_chart(
+            series,
+            self.chart_markers(scenario),
+            self.log_scale,
+            self.language,
+        ))
+    }
+
+    /// Value of the portfolio along the way, against what was put in
+    fn portfolio_chart_view(
+        &self,
+        scenario: &Scenario,
+        values: &[DailyValue],
+    ) -> Option<Element<'_, Message>> {
+        if values.is_empty() {
+            return None;
+        }
+
+        let mut series = vec![
+            Series {
+                label: self.language.tr(Msg::Value),
+                points: values
+                    .iter()
+                    .map(|value| (value.date, value.value))
+                    .collect(),
+            },
+            Series {
+                label: self.language.tr(Msg::Invested),
+                points: values
+                    .iter()
+                    .map(|value| (value.date, value.invested))
+                    .collect(),
+            },
+        ];
+        if let Some(points) = self.cpi.invested_with_inflation(values) {
+            series.push(Series {
+                label: self.language.tr(Msg::InvestedWithInflation),
+                points,
+            });
+        }
 
-        amount
-            .checked_mul(sats.sats())
-            .and_then(|sats| sats.checked_div(usd.dollars()))
-            .map(BitcoinAmount::from)
+        Some(line_chart(
+            series,
+            self.chart_markers(scenario),
+            self.log_scale,
+            self.language,
+        ))
     }
 
-    pub fn current_usd_value(&self) -> Option<DollarAmount> {
-        let sats = self.bitcoin_amount()?.sats();
+    /// `values` are the daily values of the result, if it could be evaluated
+    fn charts_view(
+        &self,
+        scenario: &Scenario,
+        values: Option<&[DailyValue]>,
+    ) -> Option<Column<'_, Message>> {
+        let price_chart = self.price_chart_view(scenario)?;
+
+        Some(
+            Column::new()
+                .spacing(5)
+                .align_items(iced::Alignment::Center)
+                .push(price_chart)
+                .push_maybe(values.and_then(|values| self.portfolio_chart_view(scenario, values)))
+                .push(
+                    checkbox(self.language.tr(Msg::LogScale), self.log_scale)
+                        .on_toggle(Message::LogScaleToggled),
+                ),
+        )
+    }
+
+    fn metrics_view(
+        &self,
+        scenario: &Scenario,
+        result: &ScenarioResult,
+        values: &[DailyValue],
+    ) -> Column<'_, Message> {
+        let metrics = Metrics::compute(scenario, result, values, self.risk_free_percent * 100);
+        let tr = |msg| text(self.language.tr(msg));
+
+        Column::new()
+            .spacing(5)
+            .align_items(iced::Alignment::Center)
+            .push(tr(Msg::TotalReturn(metrics.total_return)))
+            .push_maybe(metrics.cagr.map(|cagr| tr(Msg::YearlyGrowth(cagr))))
+            .push_maybe(
+                metrics
+                    .volatility
+                    .map(|volatility| tr(Msg::Volatility(volatility))),
+            )
+            .push_maybe(metrics.max_drawdown.map(|drawdown| {
+                tr(Msg::MaxDrawdown {
+                    loss: drawdown.loss,
+                    peak: drawdown.peak_date,
+                    trough: drawdown.trough_date,
+                })
+            }))
+            .push_maybe(
+                metrics
+                    .best_day
+                    .map(|(date, day)| tr(Msg::BestDay(date, day))),
+            )
+            .push_maybe(
+                metrics
+                    .worst_day
+                    .map(|(date, day)| tr(Msg::WorstDay(date, day))),
+            )
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(tr(Msg::SharpeRatio(metrics.sharpe_ratio)))
+                    .push(
+                        numeric_input(
+                            Some(self.risk_free_percent),
+                            1,
+                            Message::RiskFreeRateUpdated,
+                        )
+                        .placeholder(self.language.tr(Msg::TypeANumber)),
+                    )
+                    .push(tr(Msg::RiskFreeRate)),
+            )
+    }
+
+    /// Tax owed when selling on the evaluation date, lot by lot when
+    /// there were several purchases. Only the bitcoin gains are taxed, but
+    /// what's left is out of the whole portfolio, as the headline value is.
+    fn tax_view(&self, scenario: &Scenario, result: &ScenarioResult) -> Column<'_, Message> {
+        let result = self.blended(scenario, result);
+        let sale = Sale {
+            date: scenario.evaluation_date,
+            percent: self.sell_percent,
+            method: self.cost_basis_method,
+        };
+        let tax_rules = &self.tax_presets[self.tax_preset];
+        let estimate = (!tax_rules.is_none())
+            .then(|| tax_rules.estimate(&result, sale).ok())
+            .flatten();
+
+        Column::new()
+            .spacing(5)
+            .align_items(iced::Alignment::Center)
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(self.translated_pick_list(
+                        0..self.tax_presets.len(),
+                        self.tax_preset,
+                        |index: usize| Msg::TaxRules(&self.tax_presets[index]),
+                        Message::TaxPresetSelected,
+                    ))
+                    .push_maybe(estimate.map(|_| {
+                        numeric_input(Some(self.sell_percent), 10, Message::SellPercentUpdated)
+                            .placeholder(self.language.tr(Msg::TypeANumber))
+                    }))
+                    .push_maybe(estimate.map(|_| text(self.language.tr(Msg::PercentSold)))),
+            )
+            .push_maybe(
+                (estimate.is_some() && result.acquisition.purchases.len() > 1).then(|| {
+                    self.translated_pick_list(
+                        CostBasisMethod::ALL,
+                        self.cost_basis_method,
+                        Msg::CostBasisMethod,
+                        Message::CostBasisMethodSelected,
+                    )
+                }),
+            )
+            .push_maybe(estimate.map(|estimate| {
+                text(self.language.tr(Msg::TaxEstimate {
+                    short_term_gain: estimate.short_term_gain,
+                    long_term_gain: estimate.long_term_gain,
+                    tax: estimate.tax,
+                    after_tax_value: estimate.after_tax_value,
+                }))
+            }))
+    }
+
+    /// The bitcoin result next to the same contributions put elsewhere
+    fn benchmarks_view(&self, scenario: &Scenario, result: &ScenarioResult) -> Column<'_, Message> {
+        let benchmarks = [
+            Benchmark::Cash,
+            Benchmark::Savings {
+                rate_bps: self.savings_rate_percent * 100,
+            },
+        ]
+        .into_iter()
+        .chain(self.benchmark_assets.iter().map(Benchmark::Asset));
+
+        benchmarks.fold(
+            Column::new()
+                .spacing(5)
+                .push(
+                    Row::new()
+                        .spacing(10)
+                        .align_items(iced::Alignment::Center)
+                        .push(
+                            numeric_input(
+                                Some(self.savings_rate_percent),
+                                1,
+                                Message::SavingsRateUpdated,
+                            )
+                            .placeholder(self.language.tr(Msg::TypeANumber)),
+                        )
+                        .push(text(self.language.tr(Msg::SavingsRate))),
+                )
+                .push(table_row(
+                    String::new(),
+                    [Msg::Value, Msg::Gain, Msg::Multiple]
+                        .map(|msg| self.language.tr(msg))
+                        .to_vec(),
+                ))
+                .push(table_row(
+                    self.language.tr(Msg::Bitcoin),
+                    vec![
+                        self.language.amount(result.value),
+                        self.language.signed_amount(result.gain),
+                        self.language.multiple(result.multiple),
+                    ],
+                )),
+            |column, benchmark| {
+                let cells = match benchmark.evaluate(scenario, &result.acquisition) {
+                    Ok(result) => vec![
+                        self.language.amount(result.value),
+                        self.language.signed_amount(result.gain),
+                        self.language.multiple(result.multiple),
+                    ],
+                    Err(err) => vec![self.language.tr(Msg::NoResult(err))],
+                };
+                column.push(table_row(
+                    self.language.tr(Msg::Benchmark(benchmark)),
+                    cells,
+                ))
+            },
+        )
+    }
+
+    fn save_scenario(&mut self) {
+        let (Some(scenario), false) = (self.scenario(), self.scenario_name.is_empty()) else {
+            return;
+        };
+        let saved = SavedScenario::new(
+            std::mem::take(&mut self.scenario_name),
+            &scenario,
+            self.evaluation_date,
+        );
+        // Saving under an existing name updates it
+        match self
+            .saved_scenarios
+            .iter_mut()
+            .find(|existing| existing.name == saved.name)
+        {
+            Some(existing) => *existing = saved,
+            None => self.saved_scenarios.push(saved),
+        }
+        self.store_scenarios();
+    }
+
+    fn load_scenario(&mut self, saved: SavedScenario) {
+        self.amount = Some(saved.amount);
+        self.allocation = saved.allocation;
+        self.start_date = Some(saved.buy_date);
+        self.evaluation_date = saved.evaluation_date;
+        self.strategy = saved.strategy;
+        self.fee_preset = FeePreset::from_fees(saved.fees);
+        if let Some(date) = saved.evaluation_date {
+            // Makes sure we have the price if it can be fetched
+            self.price_database.get(date);
+        }
+        self.scenario_name = saved.name;
+    }
+
+    fn store_preferences(&self) {
+        if let Err(err) = self.preferences.store() {
+            println!("Saving preferences: {err}");
+        }
+    }
+
+    fn store_scenarios(&self) {
+        if let Err(err) = saved::store(&self.saved_scenarios) {
+            println!("Saving scenarios: {err}");
+        }
+    }
+
+    /// Saved scenarios to switch between, and saving the current one
+    fn sidebar_view(&self) -> Column<'_, Message> {
+        let can_save = self.scenario().is_some() && !self.scenario_name.is_empty();
+
+        self.saved_scenarios
+            .iter()
+            .enumerate()
+            .fold(
+                Column::new()
+                    .width(200)
+                    .spacing(5)
+                    .push(text(self.language.tr(Msg::SavedScenarios)).size(20)),
+                |column, (i, saved)| {
+                    column.push(
+                        Row::new()
+                            .spacing(5)
+                            .push(
+                                Button::new(Text::new(saved.name.as_str()))
+                                    .width(Length::Fill)
+                                    .on_press(Message::LoadScenario(i)),
+                            )
+                            .push(Button::new(Text::new("×")).on_press(Message::DeleteScenario(i))),
+                    )
+                },
+            )
+            .push(
+                text_input(&self.language.tr(Msg::Name), &self.scenario_name)
+                    .on_input(Message::ScenarioNameUpdated)
+                    .on_submit(Message::SaveScenario),
+            )
+            .push(
+                Button::new(Text::new(self.language.tr(Msg::Save)))
+                    .on_press_maybe(can_save.then_some(Message::SaveScenario)),
+            )
+    }
+
+    /// Saved scenarios side by side
+    fn saved_comparison_view(&self) -> Option<Column<'_, Message>> {
+        if self.saved_scenarios.is_empty() {
+            return None;
+        }
+
         let today = Utc::now().date_naive();
-        let (usd, sats_rate) = self.price_database.get(today)?;
-        println!("Found today's rates: {} = {}", usd, sats_rate);
+        let header = table_row(
+            String::new(),
+            [Msg::Invested, Msg::Value, Msg::Gain, Msg::Multiple]
+                .map(|msg| self.language.tr(msg))
+                .to_vec(),
+        );
+
+        Some(self.saved_scenarios.iter().fold(
+            Column::new().spacing(5).push(header),
+            |column, saved| {
+                let cells = match saved.scenario(today).evaluate(&self.price_database) {
+                    Ok(result) => vec![
+                        self.language.amount(result.acquisition.invested),
+                        self.language.amount(result.value),
+                        self.language.signed_amount(result.gain),
+                        self.language.multiple(result.multiple),
+                    ],
+                    Err(err) => vec![self.language.tr(Msg::NoResult(err))],
+                };
+                column.push(table_row(saved.name.clone(), cells))
+            },
+        ))
+    }
+
+    /// Only makes sense for a single conversion into a mix of bitcoin and
+    /// cash.
+    fn rebalancing_view(&self, scenario: &Scenario) -> Option<Column<'_, Message>> {
+        let Allocation::Percent(bitcoin_percent @ 1..=99) = scenario.allocation else {
+            return None;
+        };
+        if scenario.strategy != Strategy::LumpSum {
+            return None;
+        }
 
-        sats.checked_mul(usd.dollars())
-            .and_then(|usd| usd.checked_div(sats_rate.sats()))
-            .map(DollarAmount::from)
+        let rebalancing = Rebalancing {
+            amount: scenario.amount,
+            bitcoin_percent,
+            start_date: scenario.buy_date,
+            end_date: scenario.evaluation_date,
+            rule: self.rebalance_rule,
+        };
+        let result = rebalancing
+            .run(&self.price_database)
+            .ok()
+            .filter(|_| self.rebalance_rule != RebalanceRule::Never);
+        let summary = result.as_ref().and_then(|result| {
+            Some(self.language.tr(Msg::RebalancedValues {
+                rebalanced: result.rebalanced.final_value()?,
+                rebalances: result.rebalanced.rebalances,
+                buy_and_hold: result.buy_and_hold.final_value()?,
+            }))
+        });
+        let chart = result.map(|result| {
+            line_chart(
+                vec![
+                    Series {
+                        label: self.language.tr(Msg::Rebalanced),
+                        points: result.rebalanced.values,
+                    },
+                    Series {
+                        label: self.language.tr(Msg::BuyAndHold),
+                        points: result.buy_and_hold.values,
+                    },
+                ],
+                self.chart_markers(scenario),
+                self.log_scale,
+                self.language,
+            )
+        });
+
+        Some(
+            Column::new()
+                .spacing(5)
+                .align_items(iced::Alignment::Center)
+                .push(self.translated_pick_list(
+                    RebalanceRule::ALL,
+                    self.rebalance_rule,
+                    Msg::RebalanceRule,
+                    Message::RebalanceRuleSelected,
+                ))
+                .push_maybe(summary.map(text))
+                .push_maybe(chart),
+        )
+    }
+
+    /// A pick list whose options are written in the chosen language
+    fn translated_pick_list<'a, T>(
+        &self,
+        values: impl IntoIterator<Item = T>,
+        selected: T,
+        msg: impl Fn(T) -> Msg<'a>,
+        on_select: fn(T) -> Message,
+    ) -> Element<'_, Message>
+    where
+        T: Copy + PartialEq + 'static,
+    {
+        pick_list(
+            self.language.choices(values, &msg),
+            Some(self.language.choice(selected, msg)),
+            move |choice: Choice<T>| on_select(choice.value),
+        )
+        .into()
     }
 
     pub fn start() -> Result<(), iced::Error> {
@@ -52,16 +1035,58 @@ impl WhatIf {
     }
 }
 
+/// A name, then values in equal columns
+fn table_row<'a>(name: String, cells: Vec<String>) -> Row<'a, Message> {
+    cells.into_iter().fold(
+        Row::new()
+            .spacing(10)
+            .push(text(name).width(Length::FillPortion(2))),
+        |row, cell| row.push(text(cell).width(Length::FillPortion(1))),
+    )
+}
+
 // USD amount
 // Beginning date
 // Get USD/BTC quote for the day
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Message {
-    ToggleDatePicker(bool),
-    DateSelected(Date),
+    DateSelected(NaiveDate),
     PriceDatabaseUpdated(NaiveDate),
+    RetryPrice(NaiveDate),
+    EvaluationDateSelected(NaiveDate),
+    EvaluationDateReset,
+    DatePresetSelected(DatePreset),
     AmountUpdated(Option<u64>),
+    BitcoinUnitSelected(BitcoinUnit),
+    BtcThresholdUpdated(Option<u64>),
+    SubSatPrecisionToggled(bool),
+    StrategySelected(Strategy),
+    AllocationModeSelected(AllocationMode),
+    AllocationUpdated(Option<u64>),
+    RestInSelected(Option<usize>),
+    RebalanceRuleSelected(RebalanceRule),
+    FeePresetSelected(FeePreset),
+    CustomFeesUpdated(FeeModel),
+    TaxPresetSelected(usize),
+    CostBasisMethodSelected(CostBasisMethod),
+    SellPercentUpdated(Option<u64>),
+    DollarsSelected(Dollars),
+    SavingsRateUpdated(Option<u64>),
+    RiskFreeRateUpdated(Option<u64>),
+    LogScaleToggled(bool),
+    ScenarioNameUpdated(String),
+    SaveScenario,
+    LoadScenario(usize),
+    DeleteScenario(usize),
+    ThemeModeSelected(ThemeMode),
+    /// The browser switched between light and dark
+    SystemThemeChanged,
+    /// `None` to follow the system or browser
+    LanguageSelected(Option<Language>),
+    TabPressed {
+        shift: bool,
+    },
 }
 
 impl Application for WhatIf {
@@ -72,96 +1097,380 @@ impl Application for WhatIf {
 
     fn new(_flags: ()) -> (WhatIf, Command<Self::Message>) {
         let (price_database, updates_receiver) = PriceDatabase::start().unwrap();
+        let shared = share::read_url();
+        let preferences = Preferences::load().unwrap_or_else(|err| {
+            println!("Loading preferences: {err}");
+            Preferences::default()
+        });
+        if let Some(date) = shared.evaluation_date {
+            // Makes sure we have the price if it can be fetched
+            price_database.get(date);
+        }
 
-        (
-            WhatIf {
-                amount: None,
-                show_date_picker: false,
-                start_date: None,
-                price_database,
-                updates_receiver: Arc::new(Mutex::new(updates_receiver)),
-            },
-            Command::none(),
-        )
+        let mut what_if = WhatIf {
+            amount: shared.amount,
+            amount_error: None,
+            start_date: shared.buy_date,
+            notable_dates: date_presets::notable(&price_database),
+            evaluation_date: shared.evaluation_date,
+            bitcoin_format: BitcoinFormat::default(),
+            sub_sat_precision: false,
+            strategy: shared.strategy,
+            allocation: shared.allocation,
+            rest_in: None,
+            rebalance_rule: RebalanceRule::Never,
+            fee_preset: FeePreset::from_fees(shared.fees),
+            tax_presets: tax::presets(tax::local_presets().unwrap_or_else(|err| {
+                println!("Loading local tax presets: {err}");
+                None
+            })),
+            tax_preset: 0,
+            cost_basis_method: CostBasisMethod::default(),
+            sell_percent: 100,
+            cpi: Cpi::bundled(),
+            dollars: Dollars::default(),
+            benchmark_assets: benchmark::load_assets(),
+            savings_rate_percent: 4,
+            risk_free_percent: 4,
+            log_scale: false,
+            saved_scenarios: saved::load().unwrap_or_else(|err| {
+                println!("Loading saved scenarios: {err}");
+                vec![]
+            }),
+            scenario_name: String::new(),
+            preferences,
+            theme: preferences.theme.theme(),
+            language: preferences.language(),
+            summary: None,
+            price_database,
+            updates_receiver: Arc::new(Mutex::new(updates_receiver)),
+        };
+        what_if.update_summary();
+        (what_if, Command::none())
     }
 
     fn title(&self) -> String {
-        String::from("What if...")
+        // Only the headline, the details are too long for a title
+        let summary = self
+            .summary
+            .as_ref()
+            .and_then(|summary| summary.lines().next());
+        self.language.tr(Msg::Title(summary))
     }
 
-    // https://stackoverflow.com/a/75689667
     fn subscription(&self) -> Subscription<Message> {
-        iced::subscription::unfold(
+        // https://stackoverflow.com/a/75689667
+        let price_updates = iced::subscription::unfold(
             "price database updated",
             self.updates_receiver.clone(),
             move |receiver| async move {
                 let date = receiver.lock().unwrap().recv().unwrap();
                 (Message::PriceDatabaseUpdated(date), receiver)
             },
-        )
+        );
+        // Fields are focused in the order they're laid out. Only text inputs
+        // can take the focus in `iced` 0.12: buttons, pick lists, checkboxes
+        // and the calendar toggle are still only reachable with the mouse.
+        let tab = keyboard::on_key_press(|key, modifiers| match key {
+            Key::Named(Named::Tab) => Some(Message::TabPressed {
+                shift: modifiers.shift(),
+            }),
+            _ => None,
+        });
+        let system_theme = match self.preferences.theme {
+            ThemeMode::System => theme::system_changes().map(|()| Message::SystemThemeChanged),
+            ThemeMode::Light | ThemeMode::Dark => Subscription::none(),
+        };
+        Subscription::batch([price_updates, tab, system_theme])
     }
 
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
+        let shared = self.shared();
         match message {
-            Message::AmountUpdated(amount) => self.amount = amount.map(DollarAmount::from),
-            Message::DateSelected(date) => {
-                self.show_date_picker = false;
-                self.start_date = Some(NaiveDate::from(date));
+            Message::TabPressed { shift: false } => return focus_next(),
+            Message::TabPressed { shift: true } => return focus_previous(),
+            Message::AmountUpdated(amount) => {
+                match amount.map(DollarAmount::try_from).transpose() {
+                    Ok(amount) => {
+                        self.amount = amount;
+                        self.amount_error = None;
+                    }
+                    Err(err) => self.amount_error = Some(err),
+                }
             }
+            Message::DateSelected(date) => self.start_date = Some(date),
             Message::PriceDatabaseUpdated(_) => (), // Just trigger an update but nothing else to do
-            Message::ToggleDatePicker(toggle) => self.show_date_picker = toggle,
+            Message::RetryPrice(date) => {
+                self.price_database.retry(date);
+            }
+            Message::EvaluationDateSelected(date) => {
+                // Makes sure we have the price if it can be fetched
+                self.price_database.get(date);
+                self.evaluation_date = Some(date);
+            }
+            Message::EvaluationDateReset => self.evaluation_date = None,
+            Message::DatePresetSelected(preset) => self.start_date = Some(preset.date),
+            Message::BitcoinUnitSelected(unit) => self.bitcoin_format.unit = unit,
+            Message::BtcThresholdUpdated(threshold) => {
+                self.bitcoin_format.btc_threshold = threshold.unwrap_or_default()
+            }
+            Message::SubSatPrecisionToggled(enabled) => self.sub_sat_precision = enabled,
+            Message::StrategySelected(strategy) => self.strategy = strategy,
+            Message::AllocationModeSelected(mode) => {
+                self.allocation = match mode {
+                    AllocationMode::Percent => Allocation::default(),
+                    AllocationMode::Fixed => Allocation::Fixed(self.amount.unwrap_or_default()),
+                }
+            }
+            Message::RestInSelected(rest_in) => self.rest_in = rest_in,
+            Message::RebalanceRuleSelected(rule) => self.rebalance_rule = rule,
+            Message::FeePresetSelected(preset) => self.fee_preset = preset,
+            Message::CustomFeesUpdated(fees) => self.fee_preset = FeePreset::custom(fees),
+            Message::TaxPresetSelected(index) => self.tax_preset = index,
+            Message::CostBasisMethodSelected(method) => self.cost_basis_method = method,
+            Message::DollarsSelected(dollars) => self.dollars = dollars,
+            Message::SavingsRateUpdated(rate) => {
+                self.savings_rate_percent = rate.unwrap_or_default()
+            }
+            Message::LogScaleToggled(enabled) => self.log_scale = enabled,
+            Message::ScenarioNameUpdated(name) => self.scenario_name = name,
+            Message::ThemeModeSelected(mode) => {
+                self.preferences.theme = mode;
+                self.theme = mode.theme();
+                self.store_preferences();
+            }
+            Message::SystemThemeChanged => self.theme = self.preferences.theme.theme(),
+            Message::LanguageSelected(language) => {
+                self.preferences.language = language;
+                self.language = self.preferences.language();
+                self.store_preferences();
+            }
+            Message::SaveScenario => self.save_scenario(),
+            Message::LoadScenario(index) => {
+                if let Some(saved) = self.saved_scenarios.get(index).cloned() {
+                    self.load_scenario(saved);
+                }
+            }
+            Message::DeleteScenario(index) => {
+                if index < self.saved_scenarios.len() {
+                    self.saved_scenarios.remove(index);
+                    self.store_scenarios();
+                }
+            }
+            Message::RiskFreeRateUpdated(rate) => self.risk_free_percent = rate.unwrap_or_default(),
+            Message::SellPercentUpdated(percent) => {
+                self.sell_percent = percent.unwrap_or_default().min(100)
+            }
+            Message::AllocationUpdated(value) => {
+                let value = value.unwrap_or_default();
+                match self.allocation.mode() {
+                    AllocationMode::Percent => {
+                        self.allocation = Allocation::Percent(value.min(100))
+                    }
+                    AllocationMode::Fixed => match DollarAmount::try_from(value) {
+                        Ok(amount) => {
+                            self.allocation = Allocation::Fixed(amount);
+                            self.amount_error = None;
+                        }
+                        Err(err) => self.amount_error = Some(err),
+                    },
+                }
+            }
+        }
+        // Only when something shared changed, not e.g. on every price update
+        if self.shared() != shared {
+            share::write_url(&self.shared());
         }
+        self.update_summary();
         Command::none()
     }
 
-    fn view(&self) -> Element<Self::Message> {
+    fn view(&self) -> Element<'_, Self::Message> {
+        let scenario = self.scenario();
+        // Worked out once for all the views of the result
+        let result = scenario.and_then(|scenario| scenario.evaluate(&self.price_database).ok());
+        let evaluated = scenario.zip(result.as_ref());
+        let values = evaluated.and_then(|(scenario, result)| {
+            daily_values(&scenario, &result.acquisition, &self.price_database).ok()
+        });
+        let status = self.status_view();
+        let waiting = status.is_some();
         let col = Column::new()
             .max_width(600)
             .spacing(10)
             .padding(10)
             .align_items(iced::Alignment::Center)
-            .push(iced_aw::date_picker(
-                self.show_date_picker,
-                NaiveDate::from_ymd_opt(2019, 1, 1).unwrap(),
-                Button::new(Text::new("Date")).on_press(Message::ToggleDatePicker(true)),
-                Message::ToggleDatePicker(false),
-                Message::DateSelected,
-            ))
-            .push(numeric_input(
-                self.amount.map(DollarAmount::dollars),
-                10_000,
-                Message::AmountUpdated,
-            ))
-            .push_maybe(
-                self.amount
-                    .and_then(|amt| {
-                        self.start_date.and_then(|date| {
-                            self.bitcoin_amount().map(|btc| {
-                                format!(
-                                    "If you converted your entire net worth of {amt} into {btc} on {date}"
+            .push_maybe(self.date_pickers())
+            .push(self.date_presets_view())
+            .push(
+                numeric_input(
+                    self.amount.map(DollarAmount::dollars),
+                    10_000,
+                    Message::AmountUpdated,
+                )
+                .placeholder(self.language.tr(Msg::TypeANumber)),
+            )
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(self.translated_pick_list(
+                        Strategy::ALL,
+                        self.strategy,
+                        Msg::Strategy,
+                        Message::StrategySelected,
+                    ))
+                    .push(
+                        self.translated_pick_list(
+                            FeePreset::ALL
+                                .into_iter()
+                                .chain([FeePreset::custom(self.fee_preset.fees)]),
+                            self.fee_preset,
+                            |preset: FeePreset| Msg::FeePreset(preset.name),
+                            Message::FeePresetSelected,
+                        ),
+                    ),
+            )
+            .push_maybe(self.custom_fees_view())
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(
+                        numeric_input(
+                            Some(match self.allocation {
+                                Allocation::Percent(percent) => percent,
+                                Allocation::Fixed(amount) => amount.dollars(),
+                            }),
+                            match self.allocation {
+                                Allocation::Percent(_) => 5,
+                                Allocation::Fixed(_) => 1_000,
+                            },
+                            Message::AllocationUpdated,
+                        )
+                        .placeholder(self.language.tr(Msg::TypeANumber)),
+                    )
+                    .push(self.translated_pick_list(
+                        AllocationMode::ALL,
+                        self.allocation.mode(),
+                        Msg::AllocationMode,
+                        Message::AllocationModeSelected,
+                    ))
+                    .push_maybe((!self.allocation.is_everything()).then(|| {
+                        self.translated_pick_list(
+                            [None]
+                                .into_iter()
+                                .chain((0..self.benchmark_assets.len()).map(Some)),
+                            self.rest_in,
+                            |rest_in: Option<usize>| {
+                                Msg::RestIn(
+                                    rest_in.map(|index| self.benchmark_assets[index].name.as_str()),
                                 )
-                            })
-                        })
-                    })
-                    .map(text)
-                    .map(|e| e.size(30)),
+                            },
+                            Message::RestInSelected,
+                        )
+                    })),
+            )
+            .push_maybe(status)
+            .push_maybe(
+                scenario.and_then(|scenario| -> Option<Element<'_, Message>> {
+                    match scenario.acquire(&self.price_database) {
+                        Ok(acquisition) => {
+                            Some(self.acquisition_view(&scenario, &acquisition).into())
+                        }
+                        // The status already says which price is missing
+                        Err(_) if waiting => None,
+                        Err(err) => Some(
+                            text(self.language.tr(Msg::NoResult(err)))
+                                .style(self.theme().palette().danger)
+                                .into(),
+                        ),
+                    }
+                }),
+            )
+            .push_maybe(evaluated.map(|(scenario, result)| self.value_view(&scenario, result)))
+            .push_maybe(
+                scenario.and_then(|scenario| self.charts_view(&scenario, values.as_deref())),
             )
             .push_maybe(
-                self.current_usd_value()
-                    .map(|amt| format!("Your net worth today would be {amt}"))
-                    .map(text)
-                    .map(|e| e.size(50)),
+                evaluated
+                    .zip(values.as_deref())
+                    .map(|((scenario, result), values)| {
+                        self.metrics_view(&scenario, result, values)
+                    }),
+            )
+            .push_maybe(evaluated.map(|(scenario, result)| self.tax_view(&scenario, result)))
+            .push_maybe(
+                evaluated.and_then(|(scenario, result)| self.comparison_view(&scenario, result)),
+            )
+            .push_maybe(evaluated.map(|(scenario, result)| self.benchmarks_view(&scenario, result)))
+            .push_maybe(self.saved_comparison_view())
+            .push_maybe(scenario.and_then(|scenario| self.rebalancing_view(&scenario)))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(self.translated_pick_list(
+                        BitcoinUnit::ALL,
+                        self.bitcoin_format.unit,
+                        Msg::BitcoinUnit,
+                        Message::BitcoinUnitSelected,
+                    ))
+                    .push_maybe((self.bitcoin_format.unit == BitcoinUnit::Auto).then(|| {
+                        Row::new()
+                            .spacing(5)
+                            .align_items(iced::Alignment::Center)
+                            .push(text(self.language.tr(Msg::BtcThreshold)))
+                            .push(
+                                numeric_input(
+                                    Some(self.bitcoin_format.btc_threshold),
+                                    100_000,
+                                    Message::BtcThresholdUpdated,
+                                )
+                                .placeholder(self.language.tr(Msg::TypeANumber)),
+                            )
+                    }))
+                    .push(
+                        checkbox(
+                            self.language.tr(Msg::SubSatPrecision),
+                            self.sub_sat_precision,
+                        )
+                        .on_toggle(Message::SubSatPrecisionToggled),
+                    )
+                    .push(self.translated_pick_list(
+                        Dollars::ALL,
+                        self.dollars,
+                        Msg::Dollars,
+                        Message::DollarsSelected,
+                    ))
+                    .push(self.translated_pick_list(
+                        ThemeMode::ALL,
+                        self.preferences.theme,
+                        Msg::ThemeMode,
+                        Message::ThemeModeSelected,
+                    ))
+                    .push(self.translated_pick_list(
+                        [None].into_iter().chain(Language::ALL.map(Some)),
+                        self.preferences.language,
+                        Msg::Language,
+                        Message::LanguageSelected,
+                    )),
             );
 
-        Container::new(col)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .center_x()
-            .center_y()
-            .into()
+        Container::new(
+            Row::new()
+                .spacing(20)
+                .padding(10)
+                .push(self.sidebar_view())
+                .push(col),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .into()
     }
 
     fn theme(&self) -> Theme {
-        Theme::Dark
+        self.theme.clone()
     }
 }